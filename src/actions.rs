@@ -0,0 +1,253 @@
+use baguette::app::{self, ui};
+
+use crate::Application;
+
+/// something the editor can do, listed in the command palette
+pub struct Action
+{
+    pub name: &'static str,
+    /// the shortcut shown next to the name, if the action has one
+    pub shortcut: Option<&'static str>,
+    pub run: fn(&mut Application)
+}
+
+/// every action that can be invoked by name
+pub const ACTIONS: &[Action] =
+&[
+    Action { name: "new", shortcut: None, run: |editor| editor.select_spritesheet() },
+    Action { name: "new from url", shortcut: None, run: |editor| editor.sheet_url_entry = Some(String::new()) },
+    Action { name: "open saved", shortcut: None, run: |editor| { let _ = editor.load_workspace(); } },
+    Action { name: "save", shortcut: Some("ctrl+s"), run: |editor| editor.save_workspace() },
+    Action { name: "swap spritesheet", shortcut: None, run: |editor| editor.swap_spritesheet() },
+    Action { name: "browse projects", shortcut: None, run: |editor| editor.browse_projects() },
+    Action { name: "convert folder to json", shortcut: None, run: |editor| editor.convert_folder() },
+    Action { name: "import png map", shortcut: None, run: |editor| editor.import_map() },
+    Action { name: "export png", shortcut: None, run: |editor| { let _ = editor.workspace().export_png(); } },
+    Action
+    {
+        name: "export index grid",
+        shortcut: None,
+        run: |editor| editor.show_grid_export = true
+    },
+    Action
+    {
+        name: "export walkable grid",
+        shortcut: None,
+        run: |editor| editor.show_walk_export = true
+    },
+    Action
+    {
+        name: "export rust array",
+        shortcut: None,
+        run: |editor| { let _ = editor.workspace().export_rust(); }
+    },
+    Action
+    {
+        name: "export godot tilemap",
+        shortcut: None,
+        run: |editor| { let _ = editor.workspace().export_godot(); }
+    },
+    Action
+    {
+        name: "export objects",
+        shortcut: None,
+        run: |editor| { let _ = editor.workspace().export_objects(); }
+    },
+    Action
+    {
+        name: "export slicing manifest",
+        shortcut: None,
+        run: |editor| { let _ = editor.workspace().export_manifest(); }
+    },
+    Action
+    {
+        name: "export operation log",
+        shortcut: None,
+        run: |editor| { let _ = editor.workspace().export_operation_log(); }
+    },
+    Action
+    {
+        name: "replay operation log",
+        shortcut: None,
+        run: |editor| { let _ = editor.replay_operation_log(); }
+    },
+    Action
+    {
+        name: "close tab",
+        shortcut: None,
+        run: |editor| editor.close_workspace(editor.active_workspace)
+    },
+
+    Action
+    {
+        name: "select tile by index",
+        shortcut: Some("ctrl+g"),
+        run: |editor| editor.tile_index_entry = Some(String::new())
+    },
+
+    Action
+    {
+        name: "shuffle variants",
+        shortcut: None,
+        run: |editor| editor.shuffle_variants()
+    },
+
+    Action
+    {
+        name: "go to stray tiles",
+        shortcut: None,
+        run: |editor| editor.show_stray_tiles = true
+    },
+
+    Action
+    {
+        name: "fit grid to a tile",
+        shortcut: None,
+        run: |editor| editor.show_grid_fit = true
+    },
+
+    Action { name: "undo", shortcut: Some("ctrl+z"), run: |editor| editor.workspace_mut().undo() },
+    Action { name: "redo", shortcut: Some("ctrl+shift+z"), run: |editor| editor.workspace_mut().redo() },
+
+    Action { name: "copy", shortcut: Some("ctrl+c"), run: |editor| editor.copy(false) },
+    Action { name: "cut", shortcut: Some("ctrl+x"), run: |editor| editor.copy(true) },
+    Action { name: "paste", shortcut: Some("ctrl+v"), run: |editor| editor.paste() },
+    Action
+    {
+        name: "delete selected tiles",
+        shortcut: None,
+        run: |editor| editor.workspace_mut().delete_selection()
+    },
+    Action
+    {
+        name: "replace selected tiles",
+        shortcut: None,
+        run: |editor|
+        {
+            let workspace = editor.workspace_mut();
+
+            if let Some((.., uv)) = workspace.selected_tile
+            {
+                workspace.replace_selection(uv)
+            }
+        }
+    },
+    Action { name: "deselect", shortcut: Some("esc"), run: |editor| editor.workspace_mut().deselect() },
+
+    Action
+    {
+        name: "toggle tile outlines",
+        shortcut: None,
+        run: |editor| editor.show_tile_outlines = !editor.show_tile_outlines
+    },
+
+    Action
+    {
+        name: "toggle stats",
+        shortcut: None,
+        run: |editor| editor.show_stats = !editor.show_stats
+    },
+
+    Action { name: "toggle preview", shortcut: Some("tab"), run: |editor| editor.toggle_preview() },
+
+    Action { name: "clear: empty", shortcut: None, run: |editor| editor.workspace_mut().clear() },
+    Action
+    {
+        name: "clear: fill visible with selected tile",
+        shortcut: None,
+        run: |editor|
+        {
+            let visible_cells = editor.visible_cells;
+            let workspace = editor.workspace_mut();
+
+            if let (Some((.., uv)), Some(visible)) = (workspace.selected_tile, visible_cells)
+            {
+                workspace.fill(visible, uv)
+            }
+        }
+    },
+];
+
+/// returns the actions whose name contains every word of `filter`
+fn matching(filter: &str) -> impl Iterator<Item = &'static Action> + '_
+{
+    let filter = filter.to_lowercase();
+
+    ACTIONS
+        .iter()
+        .filter(move |action| filter
+            .split_whitespace()
+            .all(|word| action.name.contains(word))
+        )
+}
+
+impl Application
+{
+    /// shows the command palette if it's open, and runs the action picked from it
+    pub(crate) fn command_palette(&mut self, app: &mut app::App)
+    {
+        let Some(ref mut filter) = self.command_filter else
+        {
+            return
+        };
+
+        let mut picked = None;
+        let mut open = true;
+
+        ui::Window::new("command palette")
+            .title_bar(false)
+            .resizable(false)
+            .anchor(ui::Align2::CENTER_TOP, (0., 40.))
+            .show(app.ui().context(), |ui|
+            {
+                let text = ui.add
+                (
+                    ui::TextEdit::singleline(filter)
+                        .hint_text("type an action")
+                        .desired_width(300.)
+                );
+
+                text.request_focus();
+
+                // enter runs the best match, escape gives up
+                if ui.input(|input| input.key_pressed(ui::Key::Enter))
+                {
+                    picked = matching(filter).next()
+                }
+
+                if ui.input(|input| input.key_pressed(ui::Key::Escape))
+                {
+                    open = false
+                }
+
+                ui.separator();
+
+                ui::ScrollArea::vertical()
+                    .max_height(300.)
+                    .show(ui, |ui|
+                    {
+                        for action in matching(filter)
+                        {
+                            let button = ui::Button::new(action.name)
+                                .shortcut_text(action.shortcut.unwrap_or_default())
+                                .min_size((300., 0.).into());
+
+                            if ui.add(button).clicked()
+                            {
+                                picked = Some(action)
+                            }
+                        }
+                    });
+            });
+
+        if picked.is_some() || !open
+        {
+            self.command_filter = None
+        }
+
+        if let Some(action) = picked
+        {
+            (action.run)(self)
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use std::path::PathBuf;
+
+use image::RgbaImage;
+
+use crate::{export, tile_uv, Selection, SpriteSheet, TilePos, Tiles, Workspace};
+
+#[test]
+#[ignore]
+fn filling_and_undoing_a_million_cells()
+{
+    let mut workspace = Workspace::new();
+
+    let uv = tile_uv(3, 2, 2);
+    let area = Selection { start: TilePos { x: 0, y: 0 }, end: TilePos { x: 999, y: 999 } };
+
+    let started = std::time::Instant::now();
+    workspace.fill(area, uv);
+    let filled = started.elapsed();
+
+    assert_eq!(workspace.tiles.len(), 1_000_000);
+
+    let started = std::time::Instant::now();
+    workspace.undo();
+    let undone = started.elapsed();
+
+    assert!(workspace.tiles.is_empty());
+
+    let started = std::time::Instant::now();
+    workspace.redo();
+    let redone = started.elapsed();
+
+    assert_eq!(workspace.tiles.len(), 1_000_000);
+
+    println!("1000x1000 cells: filled in {filled:?}, undone in {undone:?}, redone in {redone:?}");
+}
+
+#[test]
+#[ignore]
+fn rasterizing_two_thousand_by_two_thousand_tiles()
+{
+    // two by two tiles of four pixels each
+    let sheet = RgbaImage::from_fn(8, 8, |x, y| image::Rgba([(x / 4 * 255) as u8, (y / 4 * 255) as u8, 0, 255]));
+    let sprite_sheet = SpriteSheet { path: PathBuf::from("sheet.png"), rows: 2, columns: 2 };
+
+    let tiles = (0..2000)
+        .flat_map(|y| (0..2000).map(move |x| (TilePos { x, y }, tile_uv(((x + y) % 4) as usize, 2, 2))))
+        .collect::<Tiles>();
+
+    let started = std::time::Instant::now();
+
+    let image = export::rasterize(&tiles, &sheet, &sprite_sheet, export::MAX_IMAGE_BYTES)
+        .expect("8000 pixels wide fits")
+        .expect("the map has tiles");
+
+    let rasterized = started.elapsed();
+
+    assert_eq!(image.dimensions(), (8000, 8000));
+
+    println!("2000x2000 tiles: rasterized in {rasterized:?}");
+}
@@ -0,0 +1,223 @@
+use std::{path::{Path, PathBuf}, sync::{atomic::AtomicUsize, mpsc}, time::SystemTime};
+
+use crate::{export, loading, settings::ProjectSettings, Tiles};
+
+/// how many pixels the longest side of a thumbnail gets
+const THUMBNAIL_SIZE: u32 = 96;
+
+/// how many pixels the longest side of a map is drawn with before it's shrunk into a thumbnail,
+/// the tiles of big maps are drawn smaller to fit, down to a single pixel
+const THUMBNAIL_SOURCE_SIZE: u32 = 4 * THUMBNAIL_SIZE;
+
+/// the most memory the map drawn for a thumbnail may take,
+/// a map too wide to fit even with single pixel tiles gets no thumbnail
+const MAX_THUMBNAIL_SOURCE_BYTES: u64 = 64 << 20;
+
+/// the saved workspaces found in a folder
+pub struct ProjectBrowser
+{
+    pub folder: PathBuf,
+    pub entries: Vec<ProjectEntry>,
+
+    /// the thumbnails drawn on a background thread, as the workspace they show and where they were saved
+    receiver: mpsc::Receiver<(PathBuf, Option<PathBuf>)>
+}
+
+pub struct ProjectEntry
+{
+    pub path: PathBuf,
+    pub name: String,
+    pub modified: Option<SystemTime>,
+    /// the preview of the tiles, `None` if the map is empty or its spritesheet can't be read
+    pub thumbnail: Option<PathBuf>,
+    /// true while the thumbnail is being drawn
+    pub drawing: bool
+}
+
+impl ProjectBrowser
+{
+    /// lists the `.bag` files of `folder`, the most recently changed first,
+    /// the thumbnails that are missing or older than their workspace are drawn on a background thread
+    pub fn scan(folder: PathBuf) -> Self
+    {
+        let mut entries = bag_files(&folder)
+            .into_iter()
+            .map(|path|
+            {
+                let thumbnail = cached_thumbnail(&path);
+
+                ProjectEntry
+                {
+                    name: path.file_stem().map_or(String::new(), |name| name.to_string_lossy().into_owned()),
+                    modified: std::fs::metadata(&path).and_then(|metadata| metadata.modified()).ok(),
+                    drawing: thumbnail.is_none(),
+                    thumbnail,
+                    path
+                }
+            })
+            .collect::<Vec<_>>();
+
+        entries.sort_by_key(|entry| std::cmp::Reverse(entry.modified));
+
+        let (sender, receiver) = mpsc::channel();
+
+        let stale = entries
+            .iter()
+            .filter(|entry| entry.drawing)
+            .map(|entry| entry.path.clone())
+            .collect::<Vec<_>>();
+
+        std::thread::spawn(move ||
+        {
+            for path in stale
+            {
+                let thumbnail = draw_thumbnail(&path);
+
+                // the browser was closed or rescanned, nobody is waiting for the rest
+                if sender.send((path, thumbnail)).is_err()
+                {
+                    break
+                }
+            }
+        });
+
+        Self { folder, entries, receiver }
+    }
+
+    /// takes the thumbnails drawn since the last call and returns where they were saved
+    pub fn poll(&mut self) -> Vec<PathBuf>
+    {
+        let mut drawn = Vec::new();
+
+        loop
+        {
+            match self.receiver.try_recv()
+            {
+                Ok((path, thumbnail)) =>
+                {
+                    if let Some(entry) = self.entries.iter_mut().find(|entry| entry.path == path)
+                    {
+                        entry.drawing = false;
+                        entry.thumbnail = thumbnail;
+                        drawn.extend(entry.thumbnail.clone())
+                    }
+                }
+                Err(mpsc::TryRecvError::Empty) => break,
+                // the thread is done, one that stopped early won't send the thumbnails it didn't get to
+                Err(mpsc::TryRecvError::Disconnected) =>
+                {
+                    for entry in &mut self.entries
+                    {
+                        entry.drawing = false
+                    }
+
+                    break
+                }
+            }
+        }
+
+        drawn
+    }
+
+    /// true while some thumbnails are still being drawn
+    pub fn drawing(&self) -> bool
+    {
+        self.entries.iter().any(|entry| entry.drawing)
+    }
+}
+
+/// the `.bag` files right inside `folder`, without going into subfolders
+pub fn bag_files(folder: &Path) -> Vec<PathBuf>
+{
+    std::fs::read_dir(folder)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "bag"))
+        .collect()
+}
+
+/// where the thumbnail of the workspace at `path` is cached, as `<name>.bag.thumb.png`
+fn thumbnail_path(path: &Path) -> PathBuf
+{
+    let mut thumbnail_path = path.as_os_str().to_owned();
+    thumbnail_path.push(".thumb.png");
+
+    thumbnail_path.into()
+}
+
+/// the cached thumbnail of the workspace at `path`, `None` if it's missing or older than the workspace
+fn cached_thumbnail(path: &Path) -> Option<PathBuf>
+{
+    let thumbnail_path = thumbnail_path(path);
+
+    let modified = |path: &Path| std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok();
+
+    modified(&thumbnail_path)
+        .is_some_and(|thumbnail| Some(thumbnail) >= modified(path))
+        .then_some(thumbnail_path)
+}
+
+/// draws the thumbnail of the workspace at `path` and caches it next to it
+fn draw_thumbnail(path: &Path) -> Option<PathBuf>
+{
+    let progress = AtomicUsize::new(0);
+    let saved = loading::read(path, &progress, &progress).ok()?;
+
+    let tiles = saved.tiles.into_iter().collect::<Tiles>();
+    let sprite_sheet = saved.sprite_sheet;
+    let (min, max) = export::bounds(&tiles)?;
+
+    let color_key = ProjectSettings::load(path).color_key;
+    let sheet = crate::open_sheet(&sprite_sheet.path, color_key).ok()?;
+
+    let (rows, columns) = (sprite_sheet.rows.max(1) as u32, sprite_sheet.columns.max(1) as u32);
+    let (tile_width, tile_height) = (sheet.width() / rows, sheet.height() / columns);
+
+    // the tiles are shrunk before drawing, so a big map never gets drawn at full size only to be thrown away
+    let cells = max.x.abs_diff(min.x).max(max.y.abs_diff(min.y)) as f64 + 1.;
+    let scale = (THUMBNAIL_SOURCE_SIZE as f64 / (cells * tile_width.max(tile_height).max(1) as f64)).min(1.);
+
+    let sheet = if scale < 1.
+    {
+        let shrink = |length: u32| (length as f64 * scale).ceil().max(1.) as u32;
+
+        image::imageops::resize
+        (
+            &sheet,
+            shrink(tile_width) * rows,
+            shrink(tile_height) * columns,
+            image::imageops::FilterType::Triangle
+        )
+    }
+    else
+    {
+        sheet
+    };
+
+    let map = export::rasterize(&tiles, &sheet, &sprite_sheet, MAX_THUMBNAIL_SOURCE_BYTES).ok()??;
+
+    // the thumbnail only ever shrinks the map, tiny maps stay pixel sharp
+    let scale = (THUMBNAIL_SIZE as f32 / map.width().max(map.height()) as f32).min(1.);
+    let (width, height) = ((map.width() as f32 * scale).max(1.) as u32, (map.height() as f32 * scale).max(1.) as u32);
+
+    let thumbnail_path = thumbnail_path(path);
+    image::imageops::thumbnail(&map, width, height).save(&thumbnail_path).ok()?;
+
+    Some(thumbnail_path)
+}
+
+/// how long ago `time` was, in the largest unit that fits
+pub fn format_age(time: SystemTime) -> String
+{
+    let seconds = SystemTime::now().duration_since(time).map_or(0, |age| age.as_secs());
+
+    match seconds
+    {
+        0..=59 => "just now".to_owned(),
+        60..=3599 => format!("{} min ago", seconds / 60),
+        3600..=86_399 => format!("{} h ago", seconds / 3600),
+        _ => format!("{} days ago", seconds / 86_400)
+    }
+}
@@ -0,0 +1,78 @@
+use baguette::app::ui;
+use serde::{Deserialize, Serialize};
+
+use crate::{Selection, TilePos, Tiles};
+
+/// tells our clipboard contents apart from any other json
+const FORMAT: &str = "baguette tiles";
+
+/// a group of tiles copied from a selection,
+/// stored as json so it can go through the system clipboard
+#[derive(Clone)]
+#[derive(Serialize, Deserialize)]
+pub struct ClipboardTiles
+{
+    format: String,
+    version: u32,
+
+    /// the size of the selection the tiles were copied from
+    pub width: i32,
+    pub height: i32,
+
+    /// the tiles relative to the top left corner of the selection
+    pub tiles: Vec<(TilePos, ui::Rect)>
+}
+
+impl ClipboardTiles
+{
+    /// collects the tiles inside `selection`
+    pub fn copy(tiles: &Tiles, selection: &Selection) -> Self
+    {
+        let origin = selection.top_left();
+        let (min, max) = (selection.min(), selection.max());
+
+        let tiles = tiles
+            .iter()
+            .filter(|(pos, _)| selection.contains(**pos))
+            .map(|(pos, uv)| (TilePos { x: pos.x - origin.x, y: pos.y - origin.y }, *uv))
+            .collect();
+
+        Self
+        {
+            format: FORMAT.to_owned(),
+            version: 1,
+
+            width: max.x - min.x + 1,
+            height: max.y - min.y + 1,
+
+            tiles
+        }
+    }
+
+    /// puts the tiles on the system clipboard,
+    /// returns false if the clipboard couldn't be reached
+    pub fn to_system(&self) -> bool
+    {
+        let Ok(json) = serde_json::to_string(self) else
+        {
+            return false
+        };
+
+        arboard::Clipboard::new()
+            .and_then(|mut clipboard| clipboard.set_text(json))
+            .is_ok()
+    }
+
+    /// reads tiles from the system clipboard,
+    /// `None` if it is unreachable or holds something else
+    pub fn from_system() -> Option<Self>
+    {
+        let text = arboard::Clipboard::new()
+            .and_then(|mut clipboard| clipboard.get_text())
+            .ok()?;
+
+        serde_json::from_str::<Self>(&text)
+            .ok()
+            .filter(|tiles| tiles.format == FORMAT)
+    }
+}
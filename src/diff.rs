@@ -0,0 +1,118 @@
+use std::{collections::HashMap, path::{Path, PathBuf}, sync::atomic::AtomicUsize};
+
+use baguette::app::ui;
+
+use crate::{settings::ProjectSettings, tile_index, SpriteSheet, TilePos, Tiles};
+
+/// how the tiles of a workspace differ from the ones saved in another `.bag` file
+pub struct MapDiff
+{
+    /// the file compared against
+    pub base_path: PathBuf,
+    base: HashMap<TilePos, ui::Rect>,
+
+    /// the cells holding a tile that were empty in the base
+    pub added: Vec<TilePos>,
+    /// the cells that held a tile in the base and are empty now
+    pub removed: Vec<TilePos>,
+    /// the cells holding another tile than in the base
+    pub changed: Vec<TilePos>
+}
+
+impl MapDiff
+{
+    /// reads the tiles saved at `base_path` and compares `tiles` against them
+    pub fn load(base_path: PathBuf, tiles: &Tiles) -> bincode::Result<Self>
+    {
+        let progress = AtomicUsize::new(0);
+        let saved = crate::loading::read(&base_path, &progress, &progress)?;
+
+        let mut diff = Self
+        {
+            base_path,
+            base: saved.tiles.into_iter().collect(),
+
+            added: Vec::new(),
+            removed: Vec::new(),
+            changed: Vec::new()
+        };
+
+        diff.compare(tiles);
+        Ok(diff)
+    }
+
+    /// compares `tiles` against the base again, once they were edited
+    pub fn compare(&mut self, tiles: &Tiles)
+    {
+        self.added = tiles
+            .keys()
+            .filter(|pos| !self.base.contains_key(pos))
+            .copied()
+            .collect();
+
+        self.removed = self.base
+            .keys()
+            .filter(|pos| !tiles.contains_key(*pos))
+            .copied()
+            .collect();
+
+        self.changed = tiles
+            .iter()
+            .filter(|(pos, uv)| self.base.get(pos).is_some_and(|base_uv| base_uv != *uv))
+            .map(|(pos, _)| *pos)
+            .collect();
+
+        // row by row from the top, so two exports of the same diff read the same
+        for cells in [&mut self.added, &mut self.removed, &mut self.changed]
+        {
+            cells.sort_by_key(|pos| (std::cmp::Reverse(pos.y), pos.x))
+        }
+    }
+
+    /// the counts of the diff in a line, like a code review would
+    pub fn summary(&self) -> String
+    {
+        format!("{} added, {} removed, {} changed", self.added.len(), self.removed.len(), self.changed.len())
+    }
+
+    /// writes the diff at `path` as json, the cells numbered as they're shown and the tiles by index,
+    /// a tile the sheet has no index for is written as null
+    pub fn export
+    (
+        &self,
+        path: &Path,
+        tiles: &Tiles,
+        sprite_sheet: &SpriteSheet,
+        settings: &ProjectSettings
+    ) -> std::io::Result<()>
+    {
+        let index = |uv: Option<&ui::Rect>| uv.and_then(|uv| tile_index(*uv, sprite_sheet.rows, sprite_sheet.columns));
+
+        let cells = |cells: &[TilePos]| cells
+            .iter()
+            .map(|pos|
+            {
+                let shown = settings.shown(*pos);
+
+                serde_json::json!
+                ({
+                    "x": shown.x,
+                    "y": shown.y,
+                    "from": index(self.base.get(pos)),
+                    "to": index(tiles.get(pos))
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let diff = serde_json::json!
+        ({
+            "base": self.base_path,
+            "summary": self.summary(),
+            "added": cells(&self.added),
+            "removed": cells(&self.removed),
+            "changed": cells(&self.changed)
+        });
+
+        std::fs::write(path, serde_json::to_string_pretty(&diff)?)
+    }
+}
@@ -0,0 +1,194 @@
+use std::{io::Read, path::{Path, PathBuf}, sync::mpsc, time::Duration};
+
+/// past this many bytes a download is given up on, no spritesheet is that big
+const MAX_SHEET_BYTES: u64 = 64 * 1024 * 1024;
+
+/// a spritesheet being downloaded on a background thread
+pub struct SheetDownload
+{
+    pub url: String,
+
+    receiver: mpsc::Receiver<Result<PathBuf, String>>
+}
+
+impl SheetDownload
+{
+    /// starts downloading the spritesheet at `url` into the cache
+    pub fn start(url: String) -> Self
+    {
+        let (sender, receiver) = mpsc::channel();
+        let thread_url = url.clone();
+
+        std::thread::spawn(move ||
+        {
+            // the receiver is gone if the download was dropped, nothing to report to
+            let _ = sender.send(fetch_sheet(&thread_url));
+        });
+
+        Self { url, receiver }
+    }
+
+    /// returns where the sheet was written once the background thread has finished
+    pub fn poll(&self) -> Option<Result<PathBuf, String>>
+    {
+        match self.receiver.try_recv()
+        {
+            Ok(result) => Some(result),
+            Err(mpsc::TryRecvError::Empty) => None,
+            Err(mpsc::TryRecvError::Disconnected) => Some(Err("the download stopped".to_owned()))
+        }
+    }
+}
+
+/// downloads the spritesheet at `url` into the cache and returns where it was written,
+/// a sheet already downloaded from the same url is fetched again so it stays current
+fn fetch_sheet(url: &str) -> Result<PathBuf, String>
+{
+    if !url.starts_with("http://") && !url.starts_with("https://")
+    {
+        return Err("only http and https urls can be downloaded".to_owned())
+    }
+
+    let response = ureq::AgentBuilder::new()
+        .timeout(Duration::from_secs(30))
+        .build()
+        .get(url)
+        .call()
+        .map_err(|err| err.to_string())?;
+
+    // a server telling the size upfront spares downloading what would be thrown away
+    let length = response.header("Content-Length").and_then(|length| length.parse::<u64>().ok());
+
+    if length.is_some_and(|length| length > MAX_SHEET_BYTES)
+    {
+        return Err(too_big())
+    }
+
+    let bytes = read_limited(response.into_reader())?;
+
+    let name = url
+        .rsplit('/')
+        .next()
+        .and_then(|name| name.split(['?', '#']).next())
+        .filter(|name| !name.is_empty())
+        .unwrap_or("sheet");
+
+    store(url, name, bytes)
+}
+
+/// the images inside the zip asset pack at `pack`, by their path in it
+pub fn pack_images(pack: &Path) -> Result<Vec<String>, String>
+{
+    let archive = std::fs::File::open(pack)
+        .map_err(|err| err.to_string())
+        .and_then(|file| zip::ZipArchive::new(file).map_err(|err| err.to_string()))?;
+
+    let mut images = archive
+        .file_names()
+        .filter(|name| name.to_ascii_lowercase().ends_with(".png"))
+        .map(str::to_owned)
+        .collect::<Vec<_>>();
+
+    images.sort();
+    Ok(images)
+}
+
+/// copies the image at `entry` of the zip asset pack at `pack` into the cache and returns where it was written
+pub fn extract_sheet(pack: &Path, entry: &str) -> Result<PathBuf, String>
+{
+    let mut archive = std::fs::File::open(pack)
+        .map_err(|err| err.to_string())
+        .and_then(|file| zip::ZipArchive::new(file).map_err(|err| err.to_string()))?;
+
+    let file = archive.by_name(entry).map_err(|err| err.to_string())?;
+
+    // an entry climbing out of the pack with `..` or an absolute path has no name it can safely be cached under
+    let name = file
+        .enclosed_name()
+        .and_then(|path| path.file_name().map(|name| name.to_string_lossy().into_owned()))
+        .ok_or_else(|| format!("{entry} isn't a path inside the pack"))?;
+
+    if file.size() > MAX_SHEET_BYTES
+    {
+        return Err(too_big())
+    }
+
+    let bytes = read_limited(file)?;
+
+    // the pack and the path inside it tell the sheet apart, two packs can hold the same file name
+    let key = format!("{}\n{entry}", pack.display());
+
+    store(&key, &name, bytes)
+}
+
+/// reads all of `reader`, refusing anything bigger than a spritesheet gets
+fn read_limited(reader: impl Read) -> Result<Vec<u8>, String>
+{
+    let mut bytes = Vec::new();
+
+    // a byte more than the limit tells a file right at it from a bigger one
+    reader
+        .take(MAX_SHEET_BYTES + 1)
+        .read_to_end(&mut bytes)
+        .map_err(|err| err.to_string())?;
+
+    if bytes.len() as u64 > MAX_SHEET_BYTES
+    {
+        Err(too_big())
+    }
+    else
+    {
+        Ok(bytes)
+    }
+}
+
+fn too_big() -> String
+{
+    format!("the spritesheet is bigger than the {} MiB limit", MAX_SHEET_BYTES / 1024 / 1024)
+}
+
+/// writes the image `bytes` into the cache under `name`, prefixed by a hash of where they came from,
+/// so the same source always lands on the same file and saved workspaces keep finding it
+fn store(source: &str, name: &str, bytes: Vec<u8>) -> Result<PathBuf, String>
+{
+    // only images the palette can show are worth keeping
+    image::load_from_memory(&bytes).map_err(|err| err.to_string())?;
+
+    let folder = crate::preferences::config_folder()
+        .ok_or("there's no config folder to cache the spritesheet in")?
+        .join("sheets");
+
+    // only the last part of the name is kept, with either separator, so it can't climb out of the folder,
+    // and a colon would make windows write to a stream of the file instead
+    let name = name
+        .rsplit(['/', '\\'])
+        .next()
+        .map(|name| name.replace(':', "_"))
+        .filter(|name| !name.is_empty() && name != "." && name != "..")
+        .unwrap_or_else(|| "sheet".to_owned());
+
+    // the image loaders go by the extension
+    let name = if name.contains('.')
+    {
+        name
+    }
+    else
+    {
+        format!("{name}.png")
+    };
+
+    let path = folder.join(format!("{:016x} {name}", stable_hash(source.as_bytes())));
+
+    std::fs::create_dir_all(&folder).map_err(|err| err.to_string())?;
+    std::fs::write(&path, bytes).map_err(|err| err.to_string())?;
+
+    Ok(path)
+}
+
+/// 64 bit FNV-1a, unlike the hasher of the standard library it gives the same value with every compiler
+fn stable_hash(bytes: &[u8]) -> u64
+{
+    bytes
+        .iter()
+        .fold(0xcbf2_9ce4_8422_2325, |hash, byte| (hash ^ *byte as u64).wrapping_mul(0x0100_0000_01b3))
+}
@@ -0,0 +1,939 @@
+use std::{collections::HashMap, path::{Path, PathBuf}, sync::{atomic::{AtomicUsize, Ordering}, mpsc, Arc}};
+
+use baguette::app::ui;
+use image::RgbaImage;
+use rayon::prelude::*;
+use serde::Serialize;
+
+use crate::{loading, objects::MapObject, settings::ProjectSettings, tile_index, tile_uv, Selection, SpriteSheet, TilePos, Tiles};
+
+/// bumped whenever the slicing math or the manifest layout changes
+const MANIFEST_VERSION: u32 = 1;
+
+/// the most memory a rasterized map may take, a 16384 pixels wide square,
+/// a single tile placed far from the others would otherwise ask for more than there is
+pub const MAX_IMAGE_BYTES: u64 = 1 << 30;
+
+/// the most cells an exported grid may have, a 2048 cells wide square,
+/// every cell becomes a value of its own in the text so the limit is lower than the image one
+pub const MAX_GRID_CELLS: u64 = 1 << 22;
+
+/// everything needed to slice the spritesheet the same way the editor does
+#[derive(Serialize)]
+struct SlicingManifest
+{
+    version: u32,
+
+    spritesheet: PathBuf,
+    /// the size of the whole image in pixels
+    width: u32,
+    height: u32,
+
+    rows: usize,
+    columns: usize,
+
+    /// always zero for now since the editor slices edge to edge,
+    /// they are here so the layout stays the same once it doesn't
+    margin: u32,
+    spacing: u32,
+
+    tile_width: u32,
+    tile_height: u32,
+
+    tiles: Vec<ManifestTile>
+}
+
+#[derive(Serialize)]
+struct ManifestTile
+{
+    index: usize,
+    uv: ui::Rect,
+    /// the top left pixel of the tile
+    x: u32,
+    y: u32
+}
+
+/// the text formats the map can be exported to as a grid of tile indices
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum GridFormat
+{
+    Csv,
+    Json
+}
+
+impl GridFormat
+{
+    pub fn extension(self) -> &'static str
+    {
+        match self
+        {
+            GridFormat::Csv => "csv",
+            GridFormat::Json => "json"
+        }
+    }
+}
+
+/// how a grid export is written
+pub struct GridExport
+{
+    pub format: GridFormat,
+    /// what an empty cell is written as, since every engine expects a different sentinel,
+    /// in json it's kept as a number or null when it reads as one and quoted otherwise
+    pub empty_cell: String,
+    /// exports only the selected cells, the json then records where they came from
+    pub selection_only: bool
+}
+
+impl Default for GridExport
+{
+    fn default() -> Self
+    {
+        Self { format: GridFormat::Csv, empty_cell: "-1".to_owned(), selection_only: false }
+    }
+}
+
+/// how the walkable cells of a map are written
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum WalkFormat
+{
+    /// the lengths of the runs of cells, alternating between solid and walkable
+    RunLength,
+    /// one bit per cell, set when it's walkable
+    Bitset
+}
+
+/// how a walkable grid export is made
+pub struct WalkExport
+{
+    pub format: WalkFormat,
+    /// counts the empty cells as walkable, otherwise only placed tiles that aren't solid are
+    pub empty_walkable: bool,
+    /// how many cells next to a solid one are made solid too, so agents keep clear of the walls
+    pub erosion: u32,
+    /// how many rings of solid cells are added around the map, so agents can't walk off its edge
+    pub padding: u32
+}
+
+impl Default for WalkExport
+{
+    fn default() -> Self
+    {
+        Self { format: WalkFormat::RunLength, empty_walkable: false, erosion: 0, padding: 0 }
+    }
+}
+
+/// writes which cells of the bounding box of `tiles` can be walked on as json at `path`, for pathfinding,
+/// a tile is walkable unless its index is one of the solid tiles of `settings`
+///
+/// the cells go row by row from the top left, and the corner is moved by the origin offset of `settings`
+pub fn export_walkable
+(
+    path: &Path,
+    tiles: &Tiles,
+    sprite_sheet: &SpriteSheet,
+    settings: &ProjectSettings,
+    options: &WalkExport
+) -> std::io::Result<()>
+{
+    let map = bounds(tiles).map(|(min, max)| Selection { start: min, end: max });
+
+    // `None` once the padding reaches past the furthest coordinate a cell can have
+    let padded = |map: Selection| -> Option<(TilePos, TilePos)>
+    {
+        let padding = i32::try_from(options.padding).ok()?;
+
+        Some
+        ((
+            TilePos { x: map.min().x.checked_sub(padding)?, y: map.min().y.checked_sub(padding)? },
+            TilePos { x: map.max().x.checked_add(padding)?, y: map.max().y.checked_add(padding)? }
+        ))
+    };
+
+    let (min, max, width, height) = match map
+    {
+        // an empty map makes an empty grid
+        None => (TilePos { x: 0, y: 0 }, TilePos { x: -1, y: -1 }, 0, 0),
+        Some(map) =>
+        {
+            let (min, max) = padded(map).ok_or_else(|| std::io::Error::new
+            (
+                std::io::ErrorKind::InvalidInput,
+                "the padding reaches past the furthest coordinate a cell can have"
+            ))?;
+
+            let (width, height) = grid_size(min, max)?;
+            (min, max, width, height)
+        }
+    };
+
+    // the padding is outside of the map, so it stays solid even where empty cells are walkable
+    let walkable = |pos: TilePos| match tiles.get(&pos)
+    {
+        None => options.empty_walkable && map.is_some_and(|map| map.contains(pos)),
+        Some(uv) => tile_index(*uv, sprite_sheet.rows, sprite_sheet.columns)
+            .is_some_and(|idx| !settings.solid_tiles.contains(&idx))
+    };
+
+    let mut cells = (0..height)
+        .flat_map(|row| (0..width).map(move |column| (column, row)))
+        .map(|(column, row)| walkable(TilePos { x: min.x + column as i32, y: max.y - row as i32 }))
+        .collect::<Vec<_>>();
+
+    // every step of erosion makes solid the walkable cells touching a solid one, diagonals included,
+    // the outside of the grid counts as solid
+    for _ in 0..options.erosion
+    {
+        let eroded = (0..cells.len())
+            .map(|cell|
+            {
+                let (column, row) = ((cell % width) as i64, (cell / width) as i64);
+
+                cells[cell] && (-1..=1).all(|y| (-1..=1).all(|x|
+                {
+                    let (column, row) = (column + x, row + y);
+
+                    (0..width as i64).contains(&column)
+                        && (0..height as i64).contains(&row)
+                        && cells[row as usize * width + column as usize]
+                }))
+            })
+            .collect();
+
+        cells = eroded
+    }
+
+    let top_left = settings.shown(TilePos { x: min.x, y: max.y });
+
+    let mut json = serde_json::json!
+    ({
+        "left": top_left.x,
+        "top": top_left.y,
+        "width": width,
+        "height": height
+    });
+
+    match options.format
+    {
+        WalkFormat::RunLength =>
+        {
+            // the first run is solid, so a grid starting walkable starts with an empty run
+            let mut runs = vec![0usize];
+            let mut current = false;
+
+            for walkable in cells
+            {
+                if walkable != current
+                {
+                    runs.push(0);
+                    current = walkable
+                }
+
+                *runs.last_mut().expect("there's always a run") += 1
+            }
+
+            json["encoding"] = "run_length".into();
+            json["runs"] = runs.into()
+        }
+        WalkFormat::Bitset =>
+        {
+            // the first cell is the lowest bit of the first byte
+            let bits = cells
+                .chunks(8)
+                .map(|byte| byte.iter().enumerate().fold(0u8, |bits, (bit, walkable)| bits | ((*walkable as u8) << bit)))
+                .map(|byte| format!("{byte:02x}"))
+                .collect::<String>();
+
+            json["encoding"] = "bitset".into();
+            json["bits"] = bits.into()
+        }
+    }
+
+    std::fs::write(path, serde_json::to_string_pretty(&json)?)
+}
+
+/// the width and height in cells of the grid going from `min` to `max`,
+/// an error if it has more than `MAX_GRID_CELLS`
+fn grid_size(min: TilePos, max: TilePos) -> std::io::Result<(usize, usize)>
+{
+    // worked out wide, the cells can span the whole range of an i32
+    let (width, height) = (max.x.abs_diff(min.x) as u64 + 1, max.y.abs_diff(min.y) as u64 + 1);
+
+    if width.checked_mul(height).is_some_and(|cells| cells <= MAX_GRID_CELLS)
+    {
+        Ok((width as usize, height as usize))
+    }
+    else
+    {
+        Err(std::io::Error::new
+        (
+            std::io::ErrorKind::InvalidInput,
+            format!("the grid would be {width} by {height} cells, more than the {MAX_GRID_CELLS} an export can hold")
+        ))
+    }
+}
+
+/// the tile indices of `area`, or of the bounding box of `tiles` without one, from the highest row down,
+/// cells that are empty or whose uv isn't part of the slicing are `None`,
+/// an error if there are more cells than `MAX_GRID_CELLS`
+fn index_grid(tiles: &Tiles, sprite_sheet: &SpriteSheet, area: Option<Selection>) -> std::io::Result<(TilePos, Vec<Vec<Option<usize>>>)>
+{
+    let Some((min, max)) = area.map(|area| (area.min(), area.max())).or_else(|| bounds(tiles)) else
+    {
+        return Ok((TilePos { x: 0, y: 0 }, Vec::new()))
+    };
+
+    grid_size(min, max)?;
+
+    let grid = (min.y..=max.y)
+        .rev()
+        .map(|y| (min.x..=max.x)
+            .map(|x| tiles
+                .get(&TilePos { x, y })
+                .and_then(|uv| tile_index(*uv, sprite_sheet.rows, sprite_sheet.columns))
+            )
+            .collect()
+        )
+        .collect();
+
+    Ok((TilePos { x: min.x, y: max.y }, grid))
+}
+
+/// writes the tile index of every cell as csv or json at `path`
+/// the notes only fit in json, where they're listed next to the cells
+///
+/// with a `region` only its cells are written, and the json remembers where it was taken from
+/// so the chunk can be put back in place later, every coordinate is moved by the origin offset of `settings`
+pub fn export_grid
+(
+    path: &Path,
+    tiles: &Tiles,
+    notes: &HashMap<TilePos, String>,
+    sprite_sheet: &SpriteSheet,
+    region: Option<Selection>,
+    settings: &ProjectSettings,
+    options: &GridExport
+) -> std::io::Result<()>
+{
+    let (top_left, grid) = index_grid(tiles, sprite_sheet, region)?;
+    let top_left = settings.shown(top_left);
+
+    let text = match options.format
+    {
+        GridFormat::Csv => grid
+            .iter()
+            .map(|row| row
+                .iter()
+                .map(|idx| idx.map_or(options.empty_cell.clone(), |idx| idx.to_string()))
+                .collect::<Vec<_>>()
+                .join(",")
+            )
+            .collect::<Vec<_>>()
+            .join("\n"),
+
+        GridFormat::Json =>
+        {
+            let empty_cell = serde_json::from_str::<serde_json::Value>(&options.empty_cell)
+                .ok()
+                .filter(|value| value.is_number() || value.is_null())
+                .unwrap_or_else(|| serde_json::Value::String(options.empty_cell.clone()));
+
+            let cells = grid
+                .iter()
+                .map(|row| row
+                    .iter()
+                    .map(|idx| idx.map_or(empty_cell.clone(), serde_json::Value::from))
+                    .collect::<Vec<_>>()
+                )
+                .collect::<Vec<_>>();
+
+            // sorted from the top left like the cells, so exports of the same map compare equal
+            let mut notes = notes
+                .iter()
+                .filter(|(pos, _)| region.is_none_or(|region| region.contains(**pos)))
+                .collect::<Vec<_>>();
+            notes.sort_by_key(|(pos, _)| (-pos.y, pos.x));
+
+            let notes = notes
+                .into_iter()
+                .map(|(pos, note)| (settings.shown(*pos), note))
+                .map(|(pos, note)| serde_json::json!({ "x": pos.x, "y": pos.y, "note": note }))
+                .collect::<Vec<_>>();
+
+            // the grid starts from the top left cell, so engines can place it back where it was
+            let mut json = serde_json::json!
+            ({
+                "left": top_left.x,
+                "top": top_left.y,
+                "cells": cells,
+                "notes": notes
+            });
+
+            if let Some(region) = region
+            {
+                let (min, max) = (settings.shown(region.min()), settings.shown(region.max()));
+
+                json["region"] = serde_json::json!
+                ({
+                    "left": min.x,
+                    "top": max.y,
+                    "width": max.x - min.x + 1,
+                    "height": max.y - min.y + 1
+                });
+            }
+
+            serde_json::to_string_pretty(&json)?
+        }
+    };
+
+    std::fs::write(path, text)
+}
+
+/// each workspace of a converted folder with the file written or why it couldn't be
+pub type ConversionResults = Vec<(PathBuf, Result<PathBuf, String>)>;
+
+/// the workspaces of a folder being converted to json index grids on a background thread
+pub struct FolderConversion
+{
+    /// the `.bag` files being converted
+    paths: Vec<PathBuf>,
+    /// amount of workspaces already converted
+    done: Arc<AtomicUsize>,
+
+    receiver: mpsc::Receiver<ConversionResults>
+}
+
+impl FolderConversion
+{
+    /// starts writing the json index grid of every workspace saved in `folder` next to it
+    pub fn start(folder: &Path, empty_cell: &str) -> Self
+    {
+        let paths = crate::browser::bag_files(folder);
+        let done = Arc::new(AtomicUsize::new(0));
+
+        let (sender, receiver) = mpsc::channel();
+
+        let options = GridExport { format: GridFormat::Json, empty_cell: empty_cell.to_owned(), selection_only: false };
+        let (thread_paths, thread_done) = (paths.clone(), done.clone());
+
+        std::thread::spawn(move ||
+        {
+            let results = thread_paths
+                .into_iter()
+                .map(|path|
+                {
+                    let result = convert_workspace(&path, &options);
+                    thread_done.fetch_add(1, Ordering::Relaxed);
+
+                    (path, result)
+                })
+                .collect();
+
+            // the receiver is gone if the conversion was dropped, nothing to report to
+            let _ = sender.send(results);
+        });
+
+        Self { paths, done, receiver }
+    }
+
+    /// returns how many workspaces were converted out of how many there are
+    pub fn progress(&self) -> (usize, usize)
+    {
+        (self.done.load(Ordering::Relaxed), self.paths.len())
+    }
+
+    /// returns the results once the background thread has finished
+    pub fn poll(&self) -> Option<ConversionResults>
+    {
+        match self.receiver.try_recv()
+        {
+            Ok(results) => Some(results),
+            Err(mpsc::TryRecvError::Empty) => None,
+            Err(mpsc::TryRecvError::Disconnected) => Some
+            (
+                self.paths.iter().map(|path| (path.clone(), Err("the conversion stopped".to_owned()))).collect()
+            )
+        }
+    }
+}
+
+/// writes the json index grid of the workspace saved at `path` next to it and returns where
+fn convert_workspace(path: &Path, options: &GridExport) -> Result<PathBuf, String>
+{
+    let progress = AtomicUsize::new(0);
+    let saved = loading::read(path, &progress, &progress).map_err(|err| err.to_string())?;
+
+    let tiles = saved.tiles.into_iter().collect::<Tiles>();
+    let settings = ProjectSettings::load(path);
+    let json_path = path.with_extension("json");
+
+    export_grid(&json_path, &tiles, &saved.notes, &saved.sprite_sheet, None, &settings, options)
+        .map_err(|err| err.to_string())?;
+
+    Ok(json_path)
+}
+
+/// writes the tile index of every cell as a rust `const` array at `path`,
+/// for small levels embedded straight in a game's code
+pub fn export_rust(path: &Path, tiles: &Tiles, sprite_sheet: &SpriteSheet, settings: &ProjectSettings) -> std::io::Result<()>
+{
+    let (top_left, grid) = index_grid(tiles, sprite_sheet, None)?;
+    let top_left = settings.shown(top_left);
+    let (width, height) = (grid.first().map_or(0, Vec::len), grid.len());
+
+    let mut source = format!
+    (
+        "// exported by baguette\n\
+        // spritesheet: {}, {} rows by {} columns\n\
+        // {width}x{height} cells holding tile indices, the first row is the highest one\n\n\
+        /// the cell the first entry of the map sits on\n\
+        pub const MAP_LEFT: i32 = {};\n\
+        pub const MAP_TOP: i32 = {};\n\n\
+        pub const MAP: [[Option<u16>; {width}]; {height}] =\n[\n",
+        sprite_sheet.path.display(), sprite_sheet.rows, sprite_sheet.columns,
+        top_left.x,
+        top_left.y
+    );
+
+    for row in grid
+    {
+        let cells = row
+            .iter()
+            .map(|idx| idx.map_or("None".to_owned(), |idx| format!("Some({idx})")))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        source += &format!("    [{cells}],\n");
+    }
+
+    source += "];\n";
+
+    std::fs::write(path, source)
+}
+
+/// writes `objects` at `path` as the json of a tiled object layer,
+/// in pixels from the top left of the index grid so both line up once imported
+pub fn export_objects(path: &Path, objects: &[MapObject], tiles: &Tiles, sprite_sheet: &SpriteSheet) -> std::io::Result<()>
+{
+    let (width, height) = image::image_dimensions(&sprite_sheet.path)
+        .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?;
+
+    let tile_width = (width / sprite_sheet.rows.max(1) as u32) as f64;
+    let tile_height = (height / sprite_sheet.columns.max(1) as u32) as f64;
+
+    // tiled counts down from the top, while the map counts up
+    let (left, top) = bounds(tiles).map_or((0, 0), |(min, max)| (min.x, max.y + 1));
+
+    let objects = objects
+        .iter()
+        .enumerate()
+        .map(|(idx, object)|
+        {
+            let (object_width, object_height) = object.size.unwrap_or_default();
+
+            let properties = object.properties
+                .iter()
+                .map(|(name, value)| serde_json::json!({ "name": name, "type": "string", "value": value }))
+                .collect::<Vec<_>>();
+
+            serde_json::json!
+            ({
+                "id": idx + 1,
+                "name": "",
+                "type": object.kind,
+                "x": (object.x - left as f64) * tile_width,
+                "y": (top as f64 - object.y - object_height) * tile_height,
+                "width": object_width * tile_width,
+                "height": object_height * tile_height,
+                "point": object.size.is_none(),
+                "rotation": 0,
+                "visible": true,
+                "properties": properties
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let layer = serde_json::json!
+    ({
+        "type": "objectgroup",
+        "name": "objects",
+        "draworder": "topdown",
+        "opacity": 1,
+        "visible": true,
+        "x": 0,
+        "y": 0,
+        "objects": objects
+    });
+
+    std::fs::write(path, serde_json::to_string_pretty(&layer)?)
+}
+
+/// writes `tiles` for godot 4, the tileset as a text resource at `path`
+/// and a scene holding a `TileMap` that uses it next to it, as `<name>.tscn`
+///
+/// godot counts rows down while the map counts them up, so every row is flipped around the origin,
+/// both files point at the spritesheet and the tileset by name from `res://`,
+/// so the three of them are expected at the root of the godot project
+pub fn export_godot(path: &Path, tiles: &Tiles, sprite_sheet: &SpriteSheet, settings: &ProjectSettings) -> std::io::Result<()>
+{
+    let invalid = |message: String| std::io::Error::new(std::io::ErrorKind::InvalidData, message);
+    let file_name = |path: &Path| path.file_name().map_or(String::new(), |name| name.to_string_lossy().into_owned());
+
+    let (width, height) = image::image_dimensions(&sprite_sheet.path).map_err(|err| invalid(err.to_string()))?;
+    let (rows, columns) = (sprite_sheet.rows.max(1), sprite_sheet.columns.max(1));
+    let (tile_width, tile_height) = (width / rows as u32, height / columns as u32);
+
+    // every tile of the sheet is declared, its atlas coordinates are its place in the sheet
+    let atlas_tiles = (0..columns)
+        .flat_map(|y| (0..rows).map(move |x| format!("{x}:{y}/0 = 0\n")))
+        .collect::<String>();
+
+    let tileset = format!
+    (
+        "[gd_resource type=\"TileSet\" load_steps=3 format=3]\n\n\
+        [ext_resource type=\"Texture2D\" path=\"res://{}\" id=\"1\"]\n\n\
+        [sub_resource type=\"TileSetAtlasSource\" id=\"TileSetAtlasSource_1\"]\n\
+        texture = ExtResource(\"1\")\n\
+        texture_region_size = Vector2i({tile_width}, {tile_height})\n\
+        {atlas_tiles}\n\
+        [resource]\n\
+        tile_size = Vector2i({tile_width}, {tile_height})\n\
+        sources/0 = SubResource(\"TileSetAtlasSource_1\")\n",
+        file_name(&sprite_sheet.path)
+    );
+
+    // godot packs each cell in three integers of two 16 bit halves each:
+    // x and y, then the source and the atlas x, then the atlas y and the alternative
+    let mut cells = Vec::with_capacity(tiles.len());
+
+    for (pos, uv) in tiles
+    {
+        let Some(idx) = tile_index(*uv, sprite_sheet.rows, sprite_sheet.columns) else
+        {
+            continue
+        };
+
+        let shown = settings.shown(*pos);
+        let (x, y) = (i16::try_from(shown.x), i16::try_from(-shown.y));
+
+        let (Ok(x), Ok(y)) = (x, y) else
+        {
+            return Err(invalid(format!("the cell {}, {} is too far for a godot tilemap", shown.x, shown.y)))
+        };
+
+        let (atlas_x, atlas_y) = ((idx % rows) as i32, (idx / rows) as i32);
+
+        cells.push([(x as u16 as i32) | ((y as u16 as i32) << 16), atlas_x << 16, atlas_y]);
+    }
+
+    let tile_data = cells
+        .iter()
+        .flatten()
+        .map(i32::to_string)
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let tileset_path = path.with_extension("tres");
+    let scene_name = path.file_stem().map_or(String::new(), |name| name.to_string_lossy().into_owned());
+
+    let scene = format!
+    (
+        "[gd_scene load_steps=2 format=3]\n\n\
+        [ext_resource type=\"TileSet\" path=\"res://{}\" id=\"1\"]\n\n\
+        [node name=\"{scene_name}\" type=\"TileMap\"]\n\
+        tile_set = ExtResource(\"1\")\n\
+        format = 2\n\
+        layer_0/tile_data = PackedInt32Array({tile_data})\n",
+        file_name(&tileset_path)
+    );
+
+    std::fs::write(&tileset_path, tileset)?;
+    std::fs::write(path.with_extension("tscn"), scene)
+}
+
+/// draws `tiles` into an image using the pixels of `sheet`,
+/// covering only the bounding box of the tiles, `None` if there are no tiles
+/// and an error if the image would take more than `max_bytes`
+///
+/// the highest row of tiles ends up at the top of the image,
+/// every row of tiles is a separate band of the image drawn on its own thread
+pub fn rasterize(tiles: &Tiles, sheet: &RgbaImage, sprite_sheet: &SpriteSheet, max_bytes: u64) -> image::ImageResult<Option<RgbaImage>>
+{
+    let Some((min, max)) = bounds(tiles) else
+    {
+        return Ok(None)
+    };
+
+    let tile_width = sheet.width() / sprite_sheet.rows.max(1) as u32;
+    let tile_height = sheet.height() / sprite_sheet.columns.max(1) as u32;
+
+    // worked out wide, the cells can span the whole range of an i32
+    let width = (max.x.abs_diff(min.x) as u64 + 1) * tile_width as u64;
+    let height = (max.y.abs_diff(min.y) as u64 + 1) * tile_height as u64;
+
+    let fits = width
+        .checked_mul(height)
+        .and_then(|pixels| pixels.checked_mul(4))
+        .is_some_and(|bytes| bytes <= max_bytes);
+
+    let (width, height) = match (u32::try_from(width), u32::try_from(height))
+    {
+        (Ok(width), Ok(height)) if fits => (width, height),
+        _ => return Err(image::ImageError::Limits(image::error::LimitError::from_kind
+        (
+            image::error::LimitErrorKind::DimensionError
+        )))
+    };
+
+    let mut image = RgbaImage::new(width, height);
+
+    // a tile too small for a single pixel draws nothing, and a band can't be empty
+    if tile_width == 0 || tile_height == 0
+    {
+        return Ok(Some(image))
+    }
+
+    // the tiles of each band, from the highest row down, with the pixel they start at in the sheet
+    let mut bands = vec![Vec::new(); max.y.abs_diff(min.y) as usize + 1];
+
+    for (pos, uv) in tiles
+    {
+        let source_x = (uv.min.x * sheet.width() as f32).round() as u32;
+        let source_y = (uv.min.y * sheet.height() as f32).round() as u32;
+
+        // a uv pointing outside of the sheet has nothing to draw
+        if source_x.checked_add(tile_width).is_none_or(|end| end > sheet.width())
+            || source_y.checked_add(tile_height).is_none_or(|end| end > sheet.height())
+        {
+            continue
+        }
+
+        bands[max.y.abs_diff(pos.y) as usize].push((pos.x.abs_diff(min.x), source_x, source_y))
+    }
+
+    // the bands don't overlap, so every thread writes its own rows without waiting on the others
+    let (sheet_stride, stride) = (sheet.width() as usize * 4, width as usize * 4);
+    let line = tile_width as usize * 4;
+
+    image
+        .par_chunks_mut(stride * tile_height as usize)
+        .zip(bands.par_iter())
+        .for_each(|(band, band_tiles)|
+        {
+            for (column, source_x, source_y) in band_tiles
+            {
+                for y in 0..tile_height as usize
+                {
+                    let source = (*source_y as usize + y) * sheet_stride + *source_x as usize * 4;
+                    let target = y * stride + *column as usize * line;
+
+                    band[target..target + line].copy_from_slice(&sheet.as_raw()[source..source + line])
+                }
+            }
+        });
+
+    Ok(Some(image))
+}
+
+/// rasterizes `tiles` and writes them as a png at `path`, the pixels of the `color_key` color left transparent
+pub fn export_png(path: &Path, tiles: &Tiles, sprite_sheet: &SpriteSheet, color_key: Option<[u8; 3]>) -> image::ImageResult<()>
+{
+    let sheet = crate::open_sheet(&sprite_sheet.path, color_key)?;
+
+    match rasterize(tiles, &sheet, sprite_sheet, MAX_IMAGE_BYTES)?
+    {
+        Some(image) => image.save(path),
+        // an empty map is still a valid, empty image
+        None => RgbaImage::new(1, 1).save(path)
+    }
+}
+
+/// the lowest and highest corners of the cells holding a tile, `None` if there are no tiles
+pub fn bounds(tiles: &Tiles) -> Option<(TilePos, TilePos)>
+{
+    let mut positions = tiles.keys();
+    let first = *positions.next()?;
+
+    Some(positions.fold((first, first), |(min, max), pos|
+    (
+        TilePos { x: min.x.min(pos.x), y: min.y.min(pos.y) },
+        TilePos { x: max.x.max(pos.x), y: max.y.max(pos.y) }
+    )))
+}
+
+/// writes a json manifest describing how `sprite_sheet` is sliced into tiles
+pub fn export_manifest(path: &Path, sprite_sheet: &SpriteSheet) -> std::io::Result<()>
+{
+    let (width, height) = image::image_dimensions(&sprite_sheet.path)
+        .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?;
+
+    let (rows, columns) = (sprite_sheet.rows.max(1), sprite_sheet.columns.max(1));
+    let (tile_width, tile_height) = (width / rows as u32, height / columns as u32);
+
+    let tiles = (0..rows * columns)
+        .map(|index| ManifestTile
+        {
+            index,
+            uv: tile_uv(index, rows, columns),
+            x: (index % rows) as u32 * tile_width,
+            y: (index / rows) as u32 * tile_height
+        })
+        .collect();
+
+    let manifest = SlicingManifest
+    {
+        version: MANIFEST_VERSION,
+
+        spritesheet: sprite_sheet.path.clone(),
+        width,
+        height,
+
+        rows,
+        columns,
+
+        margin: 0,
+        spacing: 0,
+
+        tile_width,
+        tile_height,
+
+        tiles
+    };
+
+    std::fs::write(path, serde_json::to_string_pretty(&manifest)?)
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    /// a sheet of two by two tiles, each `tile_size` pixels wide and filled with its own color
+    fn sheet(tile_size: u32) -> (RgbaImage, SpriteSheet)
+    {
+        let sheet = RgbaImage::from_fn(tile_size * 2, tile_size * 2, |x, y|
+        {
+            image::Rgba([(x / tile_size * 255) as u8, (y / tile_size * 255) as u8, 0, 255])
+        });
+
+        (sheet, SpriteSheet { path: PathBuf::from("sheet.png"), rows: 2, columns: 2 })
+    }
+
+    #[test]
+    fn a_stray_tile_far_away_is_refused_instead_of_allocated()
+    {
+        let (sheet, sprite_sheet) = sheet(16);
+
+        let tiles = Tiles::from
+        ([
+            (TilePos { x: 0, y: 0 }, tile_uv(0, 2, 2)),
+            (TilePos { x: 100_000, y: -100_000 }, tile_uv(1, 2, 2))
+        ]);
+
+        assert!(rasterize(&tiles, &sheet, &sprite_sheet, MAX_IMAGE_BYTES).is_err());
+
+        // the whole range of an i32 on both axes can't even be measured in 32 bits
+        let tiles = Tiles::from
+        ([
+            (TilePos { x: i32::MIN, y: i32::MIN }, tile_uv(0, 2, 2)),
+            (TilePos { x: i32::MAX, y: i32::MAX }, tile_uv(3, 2, 2))
+        ]);
+
+        assert!(rasterize(&tiles, &sheet, &sprite_sheet, MAX_IMAGE_BYTES).is_err());
+    }
+
+    #[test]
+    fn a_stray_tile_far_away_makes_no_index_grid()
+    {
+        let (_, sprite_sheet) = sheet(16);
+
+        let tiles = Tiles::from
+        ([
+            (TilePos { x: 0, y: 0 }, tile_uv(0, 2, 2)),
+            (TilePos { x: 2_000_000, y: -50 }, tile_uv(1, 2, 2))
+        ]);
+
+        assert!(index_grid(&tiles, &sprite_sheet, None).is_err());
+
+        let tiles = Tiles::from
+        ([
+            (TilePos { x: i32::MIN, y: i32::MIN }, tile_uv(0, 2, 2)),
+            (TilePos { x: i32::MAX, y: i32::MAX }, tile_uv(3, 2, 2))
+        ]);
+
+        assert!(index_grid(&tiles, &sprite_sheet, None).is_err());
+
+        // a lone tile at the very edge is still a single cell
+        let tiles = Tiles::from([(TilePos { x: i32::MIN, y: i32::MAX }, tile_uv(3, 2, 2))]);
+        let (top_left, grid) = index_grid(&tiles, &sprite_sheet, None).expect("a single cell fits");
+
+        assert!(top_left == TilePos { x: i32::MIN, y: i32::MAX });
+        assert_eq!(grid, vec![vec![Some(3)]]);
+    }
+
+    #[test]
+    fn walkable_grids_past_the_coordinate_limits_are_refused()
+    {
+        let (_, sprite_sheet) = sheet(16);
+        let path = std::env::temp_dir().join(format!("baguette-walkable-{}.json", std::process::id()));
+
+        let padded = WalkExport { padding: 1, ..Default::default() };
+        let edge = Tiles::from([(TilePos { x: i32::MAX, y: 0 }, tile_uv(0, 2, 2))]);
+
+        assert!(export_walkable(&path, &edge, &sprite_sheet, &ProjectSettings::default(), &padded).is_err());
+
+        let tiles = Tiles::from
+        ([
+            (TilePos { x: i32::MIN, y: 0 }, tile_uv(0, 2, 2)),
+            (TilePos { x: i32::MAX, y: 0 }, tile_uv(1, 2, 2))
+        ]);
+
+        assert!(export_walkable(&path, &tiles, &sprite_sheet, &ProjectSettings::default(), &WalkExport::default()).is_err());
+
+        // without padding the lone tile at the edge is a single cell
+        let written = export_walkable(&path, &edge, &sprite_sheet, &ProjectSettings::default(), &WalkExport::default());
+        let json = std::fs::read_to_string(&path);
+        let _ = std::fs::remove_file(&path);
+
+        written.expect("a single cell fits");
+
+        let json = serde_json::from_str::<serde_json::Value>(&json.expect("the grid was written")).expect("the grid is json");
+        assert_eq!((json["width"].as_u64(), json["height"].as_u64()), (Some(1), Some(1)));
+    }
+
+    #[test]
+    fn a_uv_far_outside_of_the_sheet_draws_nothing()
+    {
+        let (sheet, sprite_sheet) = sheet(2);
+
+        let far = ui::Rect::from_min_size(ui::pos2(1e12, 1e12), ui::vec2(0.5, 0.5));
+        let tiles = Tiles::from([(TilePos { x: 0, y: 0 }, far)]);
+
+        let image = rasterize(&tiles, &sheet, &sprite_sheet, MAX_IMAGE_BYTES)
+            .expect("a single tile fits")
+            .expect("the map has a tile");
+
+        assert_eq!(image.dimensions(), (2, 2));
+        assert!(image.pixels().all(|pixel| pixel.0 == [0, 0, 0, 0]));
+    }
+
+    #[test]
+    fn the_highest_row_is_drawn_at_the_top()
+    {
+        let (sheet, sprite_sheet) = sheet(2);
+
+        let tiles = Tiles::from
+        ([
+            (TilePos { x: -1, y: 1 }, tile_uv(1, 2, 2)),
+            (TilePos { x: 0, y: 0 }, tile_uv(2, 2, 2))
+        ]);
+
+        let image = rasterize(&tiles, &sheet, &sprite_sheet, MAX_IMAGE_BYTES)
+            .expect("a small map fits")
+            .expect("the map has tiles");
+
+        assert_eq!(image.dimensions(), (4, 4));
+        assert_eq!(image.get_pixel(0, 0).0, [255, 0, 0, 255]);
+        assert_eq!(image.get_pixel(3, 3).0, [0, 255, 0, 255]);
+        assert_eq!(image.get_pixel(3, 0).0, [0, 0, 0, 0]);
+    }
+}
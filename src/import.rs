@@ -0,0 +1,191 @@
+use std::{path::PathBuf, sync::{atomic::{AtomicUsize, Ordering}, mpsc, Arc}};
+
+use image::GenericImageView;
+
+use baguette::app::ui;
+
+use crate::{tile_uv, SpriteSheet, TilePos};
+
+/// the average difference per channel under which a map cell
+/// is still considered the same as a spritesheet tile
+const MATCH_TOLERANCE: u64 = 24;
+
+/// the tiles rebuilt from a flat map image
+pub struct ImportedMap
+{
+    pub tiles: Vec<(TilePos, ui::Rect)>,
+
+    /// the cells that didn't resemble any tile of the spritesheet
+    pub unmatched: Vec<TilePos>
+}
+
+/// a map import running on a background thread
+pub struct MapImport
+{
+    /// the spritesheet and slicing the map is matched against
+    pub sprite_sheet: SpriteSheet,
+
+    /// amount of cells already compared against the spritesheet
+    done: Arc<AtomicUsize>,
+    /// amount of cells to compare, zero until the images are decoded
+    total: Arc<AtomicUsize>,
+
+    receiver: mpsc::Receiver<image::ImageResult<ImportedMap>>
+}
+
+impl MapImport
+{
+    /// starts slicing the image at `map_path` using the tiles of `sprite_sheet`,
+    /// keyed with `color_key` the way the editor shows them
+    pub fn start(map_path: PathBuf, sprite_sheet: &SpriteSheet, color_key: Option<[u8; 3]>) -> Self
+    {
+        let done = Arc::new(AtomicUsize::new(0));
+        let total = Arc::new(AtomicUsize::new(0));
+
+        let (sender, receiver) = mpsc::channel();
+
+        let sheet_path = sprite_sheet.path.clone();
+        let (rows, columns) = (sprite_sheet.rows, sprite_sheet.columns);
+
+        let (thread_done, thread_total) = (done.clone(), total.clone());
+
+        std::thread::spawn(move ||
+        {
+            let result = slice_map(&map_path, &sheet_path, rows, columns, color_key, &thread_done, &thread_total);
+
+            // the receiver is gone if the import was dropped, nothing to report to
+            let _ = sender.send(result);
+        });
+
+        Self { sprite_sheet: sprite_sheet.clone(), done, total, receiver }
+    }
+
+    /// returns how much of the map has been processed, from 0 to 1
+    pub fn progress(&self) -> f32
+    {
+        match self.total.load(Ordering::Relaxed)
+        {
+            0 => 0.,
+            total => self.done.load(Ordering::Relaxed) as f32 / total as f32
+        }
+    }
+
+    /// returns the result once the background thread has finished
+    pub fn poll(&self) -> Option<image::ImageResult<ImportedMap>>
+    {
+        match self.receiver.try_recv()
+        {
+            Ok(result) => Some(result),
+            Err(mpsc::TryRecvError::Empty) => None,
+            Err(mpsc::TryRecvError::Disconnected) => Some
+            (
+                Err(image::ImageError::IoError(std::io::ErrorKind::Interrupted.into()))
+            )
+        }
+    }
+}
+
+fn slice_map
+(
+    map_path: &std::path::Path,
+    sheet_path: &std::path::Path,
+    rows: usize,
+    columns: usize,
+    color_key: Option<[u8; 3]>,
+    done: &AtomicUsize,
+    total: &AtomicUsize
+) -> image::ImageResult<ImportedMap>
+{
+    let map = image::open(map_path)?.into_rgba8();
+    // a color keyed sheet has transparent tiles, which have to match the transparent cells of the map
+    let sheet = crate::open_sheet(sheet_path, color_key)?;
+
+    let (rows, columns) = (rows.max(1), columns.max(1));
+
+    // the pixel size of a single tile, the map is assumed to use the same one
+    let tile_width = sheet.width() / rows as u32;
+    let tile_height = sheet.height() / columns as u32;
+
+    if tile_width == 0 || tile_height == 0
+    {
+        return Err(image::ImageError::Parameter(image::error::ParameterError::from_kind
+        (
+            image::error::ParameterErrorKind::DimensionMismatch
+        )))
+    }
+
+    let (map_columns, map_rows) = (map.width() / tile_width, map.height() / tile_height);
+
+    total.store((map_columns * map_rows) as usize, Ordering::Relaxed);
+
+    let mut imported = ImportedMap { tiles: Vec::new(), unmatched: Vec::new() };
+
+    for cell_y in 0..map_rows
+    {
+        for cell_x in 0..map_columns
+        {
+            // the top row of the image is the top row of the map
+            let pos = TilePos { x: cell_x as i32, y: -(cell_y as i32) };
+
+            let cell = image::imageops::crop_imm
+            (
+                &map, cell_x * tile_width, cell_y * tile_height, tile_width, tile_height
+            );
+
+            // fully transparent cells are just empty
+            if cell.pixels().all(|(.., pixel)| pixel[3] == 0)
+            {
+                done.fetch_add(1, Ordering::Relaxed);
+                continue
+            }
+
+            let mut nearest = None;
+
+            for idx in 0..rows * columns
+            {
+                let (row, column) = ((idx % rows) as u32, (idx / rows) as u32);
+
+                let tile = image::imageops::crop_imm
+                (
+                    &sheet, row * tile_width, column * tile_height, tile_width, tile_height
+                );
+
+                let difference = cell.pixels()
+                    .zip(tile.pixels())
+                    .map(|((.., a), (.., b))| a.0
+                        .iter()
+                        .zip(b.0)
+                        .map(|(a, b)| a.abs_diff(b) as u64)
+                        .sum::<u64>()
+                    )
+                    .sum::<u64>();
+
+                if nearest.is_none_or(|(_, nearest_difference)| difference < nearest_difference)
+                {
+                    nearest = Some((idx, difference));
+
+                    // can't get any closer than an exact match
+                    if difference == 0
+                    {
+                        break
+                    }
+                }
+            }
+
+            let channels = (tile_width * tile_height * 4) as u64;
+
+            match nearest
+            {
+                Some((idx, difference)) if difference <= MATCH_TOLERANCE * channels =>
+                {
+                    imported.tiles.push((pos, tile_uv(idx, rows, columns)))
+                }
+                _ => imported.unmatched.push(pos)
+            }
+
+            done.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    Ok(imported)
+}
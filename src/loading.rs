@@ -0,0 +1,231 @@
+use std::{collections::HashMap, io::BufRead, path::{Path, PathBuf}, sync::{atomic::{AtomicUsize, Ordering}, mpsc, Arc}};
+
+use bincode::Options;
+
+use baguette::app::ui;
+
+use crate::{objects, save_format, SpriteSheet, TilePos};
+
+/// how many tiles are read between two updates of the progress
+const PROGRESS_STEP: usize = 4096;
+
+/// everything a `.bag` file holds, before a workspace is built around it
+pub struct SavedMap
+{
+    pub sprite_sheet: SpriteSheet,
+    pub tiles: Vec<(TilePos, ui::Rect)>,
+    pub notes: HashMap<TilePos, String>,
+    pub objects: Vec<objects::MapObject>,
+    pub opacity: HashMap<TilePos, u8>
+}
+
+/// a saved workspace being read on a background thread
+pub struct WorkspaceLoad
+{
+    pub path: PathBuf,
+
+    /// amount of tiles already read
+    done: Arc<AtomicUsize>,
+    /// amount of tiles in the file, zero until the header is read
+    total: Arc<AtomicUsize>,
+
+    receiver: mpsc::Receiver<bincode::Result<SavedMap>>
+}
+
+impl WorkspaceLoad
+{
+    /// starts reading the workspace saved at `path`
+    pub fn start(path: PathBuf) -> Self
+    {
+        let done = Arc::new(AtomicUsize::new(0));
+        let total = Arc::new(AtomicUsize::new(0));
+
+        let (sender, receiver) = mpsc::channel();
+
+        let (thread_path, thread_done, thread_total) = (path.clone(), done.clone(), total.clone());
+
+        std::thread::spawn(move ||
+        {
+            let result = read(&thread_path, &thread_done, &thread_total);
+
+            // the receiver is gone if the load was dropped, nothing to report to
+            let _ = sender.send(result);
+        });
+
+        Self { path, done, total, receiver }
+    }
+
+    /// returns how much of the tiles have been read, from 0 to 1
+    pub fn progress(&self) -> f32
+    {
+        match self.total.load(Ordering::Relaxed)
+        {
+            0 => 0.,
+            total => self.done.load(Ordering::Relaxed) as f32 / total as f32
+        }
+    }
+
+    /// returns the result once the background thread has finished
+    pub fn poll(&self) -> Option<bincode::Result<SavedMap>>
+    {
+        match self.receiver.try_recv()
+        {
+            Ok(result) => Some(result),
+            Err(mpsc::TryRecvError::Empty) => None,
+            Err(mpsc::TryRecvError::Disconnected) => Some
+            (
+                Err(Box::new(bincode::ErrorKind::Io(std::io::ErrorKind::Interrupted.into())))
+            )
+        }
+    }
+}
+
+/// reads the `.bag` file at `path` a tile at a time instead of all at once,
+/// counting the tiles read in `done` out of `total`
+pub fn read(path: &Path, done: &AtomicUsize, total: &AtomicUsize) -> bincode::Result<SavedMap>
+{
+    let mut file = std::io::BufReader::new(std::fs::File::open(path)?);
+
+    // the fields of the saved data one by one, the tiles follow their count
+    let sprite_sheet: SpriteSheet = save_format().deserialize_from(&mut file)?;
+    let count = save_format().deserialize_from::<_, u64>(&mut file)? as usize;
+
+    total.store(count, Ordering::Relaxed);
+
+    // a broken count shouldn't reserve memory for tiles that aren't there
+    let mut tiles = Vec::with_capacity(count.min(PROGRESS_STEP));
+
+    for read in 1..=count
+    {
+        tiles.push(save_format().deserialize_from(&mut file)?);
+
+        if read % PROGRESS_STEP == 0
+        {
+            done.store(read, Ordering::Relaxed)
+        }
+    }
+
+    done.store(count, Ordering::Relaxed);
+
+    // files saved before cells could have notes end right after the tiles,
+    // the ones saved before objects right after the notes, and so on
+    let notes = if file.fill_buf()?.is_empty()
+    {
+        HashMap::new()
+    }
+    else
+    {
+        save_format().deserialize_from(&mut file)?
+    };
+
+    let objects = if file.fill_buf()?.is_empty()
+    {
+        Vec::new()
+    }
+    else
+    {
+        save_format().deserialize_from(&mut file)?
+    };
+
+    let opacity = if file.fill_buf()?.is_empty()
+    {
+        HashMap::new()
+    }
+    else
+    {
+        save_format().deserialize_from(&mut file)?
+    };
+
+    Ok(SavedMap { sprite_sheet, tiles, notes, objects, opacity })
+}
+
+#[cfg(test)]
+mod tests
+{
+    use std::io::Write;
+
+    use crate::{tile_uv, SavedData, Workspace};
+
+    use super::*;
+
+    /// a file in the temp folder, named after the test so tests running together don't share one
+    fn temp_path(name: &str) -> PathBuf
+    {
+        std::env::temp_dir().join(format!("baguette-{name}-{}.bag", std::process::id()))
+    }
+
+    fn sprite_sheet() -> SpriteSheet
+    {
+        SpriteSheet { path: PathBuf::from("sheet.png"), rows: 4, columns: 2 }
+    }
+
+    #[test]
+    fn saved_workspaces_read_back_the_same()
+    {
+        let path = temp_path("round-trip");
+
+        let corner = TilePos { x: -3, y: -7 };
+        let far = TilePos { x: i32::MIN, y: i32::MAX };
+
+        let mut workspace = Workspace::new();
+        workspace.sprite_sheet = Some(sprite_sheet());
+        workspace.workspace_path = Some(path.clone());
+
+        workspace.tiles.insert(corner, tile_uv(5, 4, 2));
+        workspace.tiles.insert(TilePos { x: 2, y: -1 }, tile_uv(0, 4, 2));
+        workspace.tiles.insert(far, tile_uv(7, 4, 2));
+        workspace.notes.insert(TilePos { x: -1, y: 0 }, "spawn".to_owned());
+        workspace.objects.push(objects::MapObject::area("trigger".to_owned(), [-2.5, 1.], [0., -4.]));
+        workspace.opacity.insert(corner, 96);
+
+        workspace.save(None).expect("the workspace saves");
+
+        let (done, total) = (AtomicUsize::new(0), AtomicUsize::new(0));
+        let saved = read(&path, &done, &total);
+
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_file(path.with_extension("bag.meta"));
+
+        let saved = saved.expect("the saved workspace reads back");
+
+        assert_eq!(saved.sprite_sheet.path, PathBuf::from("sheet.png"));
+        assert_eq!((saved.sprite_sheet.rows, saved.sprite_sheet.columns), (4, 2));
+
+        assert!(saved.tiles.iter().copied().eq(workspace.tiles.iter().map(|(pos, uv)| (*pos, *uv))));
+        assert_eq!((done.load(Ordering::Relaxed), total.load(Ordering::Relaxed)), (3, 3));
+
+        assert!(saved.notes == workspace.notes);
+        assert!(saved.opacity == workspace.opacity);
+
+        assert_eq!(saved.objects.len(), 1);
+        assert_eq!(saved.objects[0].kind, "trigger");
+        assert_eq!((saved.objects[0].x, saved.objects[0].y, saved.objects[0].size), (-2.5, -4., Some((2.5, 5.))));
+    }
+
+    #[test]
+    fn files_saved_before_the_trailing_sections_still_read()
+    {
+        let path = temp_path("old-format");
+
+        let data = SavedData
+        {
+            sprite_sheet: sprite_sheet(),
+            tiles: vec![(TilePos { x: -12, y: -40 }, tile_uv(3, 4, 2))]
+        };
+
+        let written = std::fs::File::create(&path)
+            .and_then(|mut file| file.write_all(&save_format().serialize(&data).expect("the data serializes")));
+
+        written.expect("the old file is written");
+
+        let saved = read(&path, &AtomicUsize::new(0), &AtomicUsize::new(0));
+        let _ = std::fs::remove_file(&path);
+
+        let saved = saved.expect("the old file reads back");
+
+        assert!(saved.tiles == data.tiles);
+        assert!(saved.notes.is_empty());
+        assert!(saved.objects.is_empty());
+        assert!(saved.opacity.is_empty());
+    }
+}
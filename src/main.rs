@@ -1,12 +1,32 @@
-use std::{io::{Read, Write}, path::PathBuf,};
+use std::{collections::{HashMap, HashSet}, io::Write, path::PathBuf,};
 
 use baguette::{*, app::ui};
 
 use egui_plot as plot;
 use serde::{Deserialize, Serialize};
 
+use bincode::Options;
 use indexmap::IndexMap;
 
+mod actions;
+/// timings of the heaviest operations, run them with `cargo test --release benches -- --ignored --nocapture`,
+/// they're ignored tests since the editor has no library a `benches` target could link against
+#[cfg(test)]
+mod benches;
+mod browser;
+mod clipboard;
+mod diff;
+mod download;
+mod export;
+mod import;
+mod loading;
+mod objects;
+mod oplog;
+mod preferences;
+mod rng;
+mod settings;
+mod watcher;
+
 fn main()
 {
     baguette::new()
@@ -26,20 +46,139 @@ struct SpriteSheet
 
 struct Application
 {
-    /// the path we loaded the spritesheet from 
-    sprite_sheet: Option<SpriteSheet>,
-    workspace_path: Option<PathBuf>,
-    asset_preview_scale: f32,
-    selected_tile: Option<(usize, ui::Rect)>,
+    /// the open tabs, there's always at least one
+    workspaces: Vec<Workspace>,
+    /// the tab that is shown and edited
+    active_workspace: usize,
+    /// the tab with unsaved changes waiting for its closing to be confirmed
+    closing_workspace: Option<usize>,
 
-    /// drag state to check if we need to draw
-    dragging: Option<Tiles>,
+    /// what the primary button does on the canvas
+    current_tool: Tool,
+    /// the kind given to newly placed objects, the last one typed in
+    object_kind: String,
+    /// the chance from 0 to 1 that a cell touched by a stroke gets painted
+    brush_density: f32,
+    /// how many cells wide a stroke is at full pressure
+    max_brush_size: i32,
+    /// the line freehand strokes are reflected across, `None` while they aren't
+    symmetry: Option<Symmetry>,
+    /// lets a fill started on a ctrl clicked variant spread over all the variants, as one terrain
+    fill_across_variants: bool,
+    /// how opaque the painted tiles are drawn, from 0 to 1
+    paint_opacity: f32,
+    /// how hard a pen last pressed from 0 to 1, stays full without a pen
+    pen_pressure: f32,
+    /// what dragging with the secondary button does
+    secondary_button: SecondaryButton,
+    /// which cells a stroke is allowed to paint over
+    paint_mode: PaintMode,
+    /// what dragging on the canvas draws
+    brush_shape: BrushShape,
+    /// draws only the border of shapes, leaving their inside untouched
+    hollow_shapes: bool,
+    /// how many cells thick the border of hollow shapes is
+    shape_border: i32,
+    /// pulls the brush against the edge of the closest placed tiles
+    magnetic_placement: bool,
+    /// the folders the file dialogs open in for spritesheets and for workspaces
+    sheet_folder: Option<PathBuf>,
+    workspace_folder: Option<PathBuf>,
+    /// paints whole blocks of the super-grid at once, for blocking out a level
+    snap_to_super_grid: bool,
+    /// how many cells a side of a super-grid block spans
+    super_grid_size: i32,
+    /// how many pixels wide a cell needs to be on screen before it can be painted
+    min_cell_pixels: f32,
+    /// where pasted tiles are placed relative to the cursor
+    stamp_anchor: Anchor,
+    /// held while scrolling to go through the recently used tiles
+    tile_cycle_modifier: ScrollModifier,
+    rng: rng::Rng,
 
-    /// the tiles we will actually draw
-    tiles: Tiles,
+    /// the cell under the cursor, if the canvas is hovered
+    hovered_cell: Option<TilePos>,
+    /// the cell the current stroke reached last frame
+    last_painted_cell: Option<TilePos>,
+    /// the cells the canvas showed last frame
+    visible_cells: Option<Selection>,
+    /// the size of the canvas in pixels and the bounds it showed last frame,
+    /// to keep the view still when the canvas gets resized
+    canvas_view: Option<(ui::Vec2, plot::PlotBounds)>,
+    /// how wide a cell gets next frame around the same center, in points, set by clicking the zoom indicator
+    zoom_reset: Option<f64>,
+    /// the last tiles copied, used when the system clipboard holds something else
+    clipboard: Option<clipboard::ClipboardTiles>,
 
-    undos: TilesHistory,
-    redos: TilesHistory
+    /// the text typed in the command palette, `None` while it's closed
+    command_filter: Option<String>,
+    /// the tile index being typed, `None` while the popup is closed
+    tile_index_entry: Option<String>,
+    /// the cell whose note is being edited and the text typed so far
+    note_entry: Option<(TilePos, String)>,
+    /// the object a right click opened the ordering menu of, and where on screen
+    object_menu: Option<(usize, ui::Pos2)>,
+    /// the placed tile a right click opened the menu of, and where on screen
+    cell_menu: Option<(TilePos, ui::Pos2)>,
+    /// the url of a spritesheet being typed, `None` while the popup is closed
+    sheet_url_entry: Option<String>,
+    /// a spritesheet being downloaded, its tab opens once it's done
+    sheet_download: Option<download::SheetDownload>,
+    /// a zip asset pack and the images in it, shown until one of them is picked
+    asset_pack: Option<(PathBuf, Vec<String>)>,
+
+    /// draws a border around every cell holding a tile
+    show_tile_outlines: bool,
+    /// numbers the cells along the top and left edges of the canvas
+    show_coordinates: bool,
+    /// draws lines across the whole canvas through the hovered cell
+    show_crosshair: bool,
+    /// writes the index of the hovered palette tile over it
+    show_palette_indices: bool,
+    /// writes in the corner of the canvas which texture the tiles are drawn with
+    show_texture_debug: bool,
+    /// hides the panels, the grid and every overlay, leaving the map as a game would show it
+    preview: bool,
+    /// shows the window with the numbers about the current map
+    show_stats: bool,
+    /// shows the whole spritesheet to size the grid from a single tile
+    show_grid_fit: bool,
+    /// the corners of the tile dragged over the spritesheet, in pixels
+    grid_sample: Option<(ui::Pos2, ui::Pos2)>,
+    /// shows the options for exporting the tile indices
+    show_grid_export: bool,
+    grid_export: export::GridExport,
+    /// shows the options for exporting the walkable cells and picking the solid tiles
+    show_walk_export: bool,
+    walk_export: export::WalkExport,
+    /// lists the tiles placed far away from the rest
+    show_stray_tiles: bool,
+    /// the cell the view moves over next frame
+    jump_to: Option<TilePos>,
+
+    /// the saved workspaces of a folder, shown with their thumbnails
+    project_browser: Option<browser::ProjectBrowser>,
+    /// the folder being converted to json, its results show up once every workspace is done
+    folder_conversion: Option<export::FolderConversion>,
+    /// what converting a folder to json did to each workspace, shown until dismissed
+    batch_results: Option<export::ConversionResults>,
+
+    /// a png map being sliced back into tiles, and the tab it was started from
+    map_import: Option<(import::MapImport, usize)>,
+    /// a saved workspace still being read, its tab opens once it's done
+    workspace_load: Option<loading::WorkspaceLoad>,
+    /// the cells of the last import that didn't match any tile
+    unmatched_cells: Option<Vec<TilePos>>,
+
+    /// the preferences as they were last written, to only write them again once they change
+    preferences: preferences::Preferences,
+    /// when the spritesheet file was last looked at for changes made outside of the editor
+    sheet_checked: std::time::Instant,
+    /// watches the spritesheets and reloads them as soon as they're saved, instead of asking
+    auto_reload_sheet: bool,
+
+    /// a short message shown in the corner and when it appeared
+    toast: Option<(String, std::time::Instant)>
 }
 
 impl app::State for Application
@@ -47,35 +186,607 @@ impl app::State for Application
     fn new(app: &mut app::App) -> Self where Self: Sized
     {
         egui_extras::install_image_loaders(app.ui().context());
+
+        // the brush and tool pick up where the last session left them
+        let preferences = preferences::Preferences::load();
         
-        Self
+        let mut editor = Self
         {
-            sprite_sheet: None,
-            asset_preview_scale: 1.,
-            selected_tile: None,
+            workspaces: vec![Workspace::new()],
+            active_workspace: 0,
+            closing_workspace: None,
 
-            tiles: Tiles::default(),
-            undos: TilesHistory::new(),
-            redos: TilesHistory::new(),
+            current_tool: preferences.tool,
+            object_kind: "spawn".to_owned(),
+            brush_density: preferences.brush_density,
+            symmetry: None,
+            fill_across_variants: false,
+            paint_opacity: 1.,
+            max_brush_size: preferences.brush_size,
+            pen_pressure: 1.,
+            secondary_button: preferences.secondary_button,
+            paint_mode: preferences.paint_mode,
+            brush_shape: preferences.brush_shape,
+            hollow_shapes: preferences.hollow_shapes,
+            shape_border: preferences.shape_border,
+            magnetic_placement: preferences.magnetic_placement,
+            snap_to_super_grid: preferences.snap_to_super_grid,
+            sheet_folder: preferences.sheet_folder.clone(),
+            workspace_folder: preferences.workspace_folder.clone(),
+            super_grid_size: preferences.super_grid_size.max(1),
+            min_cell_pixels: 4.,
+            stamp_anchor: preferences.stamp_anchor,
+            tile_cycle_modifier: ScrollModifier::Alt,
+            rng: rng::Rng::new(0x5eed),
 
-            dragging: None,
-            workspace_path: None,
+            hovered_cell: None,
+            last_painted_cell: None,
+            visible_cells: None,
+            canvas_view: None,
+            zoom_reset: None,
+            clipboard: None,
+
+            command_filter: None,
+            tile_index_entry: None,
+            note_entry: None,
+            object_menu: None,
+            cell_menu: None,
+            sheet_url_entry: None,
+            sheet_download: None,
+            asset_pack: None,
+
+            show_tile_outlines: false,
+            show_coordinates: true,
+            show_crosshair: false,
+            show_palette_indices: false,
+            show_texture_debug: false,
+            preview: false,
+            show_stats: false,
+            show_grid_fit: false,
+            grid_sample: None,
+            show_grid_export: false,
+            grid_export: export::GridExport::default(),
+            show_walk_export: false,
+            walk_export: export::WalkExport::default(),
+            show_stray_tiles: false,
+            jump_to: None,
+
+            project_browser: None,
+            folder_conversion: None,
+            batch_results: None,
+
+            map_import: None,
+            workspace_load: None,
+            unmatched_cells: None,
+
+            auto_reload_sheet: preferences.auto_reload_sheet,
+            preferences,
+            sheet_checked: std::time::Instant::now(),
+
+            toast: None
+        };
+
+        if let Some(path) = launch_file()
+        {
+            editor.open_file(path)
         }
+
+        editor
     }
 
     fn update(&mut self, app: &mut app::App, _: &app::StateEvent)
     {
-        self.top_panel(app);
-        self.bottom_panel(app);
+        if !self.preview
+        {
+            self.top_panel(app);
+            self.bottom_panel(app);
+            self.tool_panel(app);
+        }
         
         self.editor_grid(app);
+        self.import_window(app);
+        self.close_workspace_window(app);
+        self.workspace_load_window(app);
+        self.stats_window(app);
+        self.tile_index_window(app);
+        self.note_window(app);
+        self.sheet_error_window(app);
+        self.missing_tiles_window(app);
+        self.sheet_url_window(app);
+        self.sheet_download_window(app);
+        self.asset_pack_window(app);
+        self.object_window(app);
+        self.object_menu(app);
+        self.cell_menu(app);
+        self.grid_fit_window(app);
+        self.grid_export_window(app);
+        self.walk_export_window(app);
+        self.stray_tiles_window(app);
+        self.diff_window(app);
+        self.project_browser_window(app);
+        self.batch_results_window(app);
+        self.command_palette(app);
+        self.toast(app);
 
         self.check_input(app);
+        self.check_sheet_changes(app);
+        self.save_preferences();
+    }
+}
+
+/// what dragging with the secondary mouse button does on the canvas
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Serialize, Deserialize)]
+enum SecondaryButton
+{
+    Erase,
+    PaintSecondaryTile
+}
+
+/// the key that makes the scroll wheel cycle through the recent tiles instead of moving the view
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ScrollModifier
+{
+    Alt,
+    Shift,
+    Ctrl
+}
+
+impl ScrollModifier
+{
+    fn held(self, modifiers: ui::Modifiers) -> bool
+    {
+        match self
+        {
+            ScrollModifier::Alt => modifiers.alt,
+            ScrollModifier::Shift => modifiers.shift,
+            ScrollModifier::Ctrl => modifiers.command
+        }
+    }
+}
+
+/// what dragging on the canvas draws
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Serialize, Deserialize)]
+enum BrushShape
+{
+    /// paints every cell the cursor goes over
+    Freehand,
+    /// paints the rectangle between where the drag started and where it ends
+    Rectangle,
+    /// paints the ellipse fitting in that same rectangle
+    Ellipse
+}
+
+impl BrushShape
+{
+    /// returns the cells the shape covers inside `area`,
+    /// or only the ones `border` cells deep from its edge if it's `hollow`
+    fn cells(self, area: Selection, hollow: bool, border: i32) -> Vec<TilePos>
+    {
+        let (min, max) = (area.min(), area.max());
+
+        // the center and radii of the ellipse, measured on cell centers
+        let center = ((min.x + max.x) as f64 / 2., (min.y + max.y) as f64 / 2.);
+        let radii = ((max.x - min.x + 1) as f64 / 2., (max.y - min.y + 1) as f64 / 2.);
+
+        let inside = |pos: TilePos| match self
+        {
+            BrushShape::Ellipse =>
+            {
+                let (dx, dy) = ((pos.x as f64 - center.0) / radii.0, (pos.y as f64 - center.1) / radii.1);
+                dx * dx + dy * dy <= 1.
+            }
+            _ => area.contains(pos)
+        };
+
+        // a cell is on the border if stepping `border` cells in any direction leaves the shape
+        let on_border = |pos: TilePos| [(1, 0), (-1, 0), (0, 1), (0, -1)]
+            .iter()
+            .any(|(x, y)| !inside(TilePos { x: pos.x + x * border, y: pos.y + y * border }));
+
+        (min.y..=max.y)
+            .flat_map(|y| (min.x..=max.x).map(move |x| TilePos { x, y }))
+            .filter(|pos| inside(*pos) && (!hollow || on_border(*pos)))
+            .collect()
+    }
+}
+
+/// which cells a stroke paints, erasing isn't affected
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Serialize, Deserialize)]
+enum PaintMode
+{
+    Replace,
+    /// fills the gaps without touching placed tiles
+    OnlyEmpty,
+    /// swaps the tiles already placed without growing the map
+    OnlyOccupied
+}
+
+impl PaintMode
+{
+    /// tells if a stroke in this mode can paint a cell that is `occupied` or not
+    fn allows(self, occupied: bool) -> bool
+    {
+        match self
+        {
+            PaintMode::Replace => true,
+            PaintMode::OnlyEmpty => !occupied,
+            PaintMode::OnlyOccupied => occupied
+        }
+    }
+}
+
+/// where a multi tile stamp sits relative to the cell under the cursor
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Serialize, Deserialize)]
+enum Anchor
+{
+    TopLeft,
+    Center,
+    BottomCenter
+}
+
+impl Anchor
+{
+    /// returns the offset from the cursor to the top left cell of a `width` by `height` stamp
+    fn top_left_offset(self, width: i32, height: i32) -> TilePos
+    {
+        match self
+        {
+            Anchor::TopLeft => TilePos { x: 0, y: 0 },
+            Anchor::Center => TilePos { x: -(width / 2), y: height / 2 },
+            Anchor::BottomCenter => TilePos { x: -(width / 2), y: height - 1 }
+        }
+    }
+}
+
+/// what the primary button does on the canvas, picked from the toolbar
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Serialize, Deserialize)]
+enum Tool
+{
+    /// strokes or shapes with the selected tile, following the brush menu
+    Paint,
+    Erase,
+    /// paints every cell connected to the clicked one holding the same tile,
+    /// without leaving the selection if the click was inside it
+    Fill,
+    Rectangle,
+    Line,
+    /// selects the tile under the cursor
+    Pick,
+    Select,
+    /// drags the selected tiles around
+    Move,
+    /// places points and areas that aren't tied to the grid
+    Object
+}
+
+impl Tool
+{
+    /// the tools in the order of the toolbar
+    const ALL: [Tool; 9] =
+    [
+        Tool::Paint, Tool::Erase, Tool::Fill, Tool::Rectangle,
+        Tool::Line, Tool::Pick, Tool::Select, Tool::Move, Tool::Object
+    ];
+
+    fn icon(self) -> &'static str
+    {
+        match self
+        {
+            Tool::Paint => "✏",
+            Tool::Erase => "🗑",
+            Tool::Fill => "💧",
+            Tool::Rectangle => "⬜",
+            Tool::Line => "╱",
+            Tool::Pick => "💉",
+            Tool::Select => "🔲",
+            Tool::Move => "✋",
+            Tool::Object => "📍"
+        }
+    }
+
+    fn name(self) -> &'static str
+    {
+        match self
+        {
+            Tool::Paint => "paint",
+            Tool::Erase => "erase",
+            Tool::Fill => "fill, kept inside the selection when clicked in it",
+            Tool::Rectangle => "rectangle",
+            Tool::Line => "line",
+            Tool::Pick => "pick a placed tile",
+            Tool::Select => "select",
+            Tool::Move => "move the selection",
+            Tool::Object => "place objects, click for a point and drag for an area"
+        }
+    }
+
+    /// tells if the selected tile is previewed under the cursor with this tool
+    fn paints(self) -> bool
+    {
+        matches!(self, Tool::Paint | Tool::Fill | Tool::Rectangle | Tool::Line)
+    }
+
+    /// what dragging with the primary button paints, nothing if the tool doesn't paint by dragging
+    fn brush(self, selected_tile: Option<(usize, ui::Rect)>) -> Option<ui::Rect>
+    {
+        match self
+        {
+            Tool::Paint | Tool::Rectangle | Tool::Line => selected_tile.map(|(.., uv)| uv),
+            Tool::Erase => Some(ui::Rect::NOTHING),
+            // the other tools act on click or on the selection
+            _ => None
+        }
+    }
+
+    /// tells if a drag draws a shape once released instead of a stroke following the cursor
+    fn draws_shapes(self, brush_shape: BrushShape) -> bool
+    {
+        matches!(self, Tool::Rectangle | Tool::Line) || brush_shape != BrushShape::Freehand
+    }
+
+    /// returns the cells of the shape dragged over `area`
+    fn shape_cells(self, brush_shape: BrushShape, area: Selection, hollow: bool, border: i32) -> Vec<TilePos>
+    {
+        match self
+        {
+            Tool::Line => line_cells(area.start, area.end),
+            Tool::Rectangle => BrushShape::Rectangle.cells(area, hollow, border),
+            _ => brush_shape.cells(area, hollow, border)
+        }
+    }
+
+    /// what hovering the canvas shows before clicking, given the cells the brush covers under the cursor
+    /// and the cells a fill would reach from there, only worked out for the fill tool
+    fn hover_preview(self, selected_uv: Option<ui::Rect>, brush: Vec<TilePos>, fill: impl FnOnce() -> Vec<TilePos>) -> HoverPreview
+    {
+        match (self, selected_uv)
+        {
+            (Tool::Erase, _) => HoverPreview::Erase(brush),
+            (Tool::Paint | Tool::Rectangle | Tool::Line, Some(uv)) => HoverPreview::Tiles(uv, brush),
+            (Tool::Fill, Some(uv)) =>
+            {
+                let cells = fill();
+
+                let Some(&start) = cells.first() else
+                {
+                    return HoverPreview::Nothing
+                };
+
+                let extent = cells.iter().fold(Selection { start, end: start }, |extent, pos| Selection
+                {
+                    start: TilePos { x: extent.start.x.min(pos.x), y: extent.start.y.min(pos.y) },
+                    end: TilePos { x: extent.end.x.max(pos.x), y: extent.end.y.max(pos.y) }
+                });
+
+                HoverPreview::Region { uv, start, extent }
+            }
+            _ => HoverPreview::Nothing
+        }
+    }
+}
+
+/// what the canvas draws under the cursor to tell what a click with the current tool would do
+enum HoverPreview
+{
+    Nothing,
+    /// the tile drawn faded over every cell it would be painted on
+    Tiles(ui::Rect, Vec<TilePos>),
+    /// the cells that would be emptied, outlined in red
+    Erase(Vec<TilePos>),
+    /// the tile on the clicked cell and the bounds of the region a fill would spread over
+    Region { uv: ui::Rect, start: TilePos, extent: Selection }
+}
+
+/// a line of the grid freehand strokes paint and erase on both sides of
+#[derive(Clone, Copy, PartialEq)]
+struct Symmetry
+{
+    /// reflects up and down across a row instead of left and right across a column
+    horizontal: bool,
+    /// where the line crosses its axis in half cells, odd values go through the middle of a cell
+    half_cells: i32
+}
+
+impl Symmetry
+{
+    /// the cell on the other side of the line from `pos`
+    fn reflect(self, pos: TilePos) -> TilePos
+    {
+        if self.horizontal
+        {
+            TilePos { x: pos.x, y: self.half_cells - 1 - pos.y }
+        }
+        else
+        {
+            TilePos { x: self.half_cells - 1 - pos.x, y: pos.y }
+        }
+    }
+
+    /// `cells` followed by their reflections, leaving out the ones landing back on `cells` along the line
+    fn mirrored(self, cells: Vec<TilePos>) -> Vec<TilePos>
+    {
+        let reflections = cells
+            .iter()
+            .map(|pos| self.reflect(*pos))
+            .filter(|pos| !cells.contains(pos))
+            .collect::<Vec<_>>();
+
+        cells.into_iter().chain(reflections).collect()
     }
 }
 
+/// a single tilemap being edited, shown as a tab
+struct Workspace
+{
+    /// the path we loaded the spritesheet from 
+    sprite_sheet: Option<SpriteSheet>,
+    workspace_path: Option<PathBuf>,
+    selected_tile: Option<(usize, ui::Rect)>,
+    /// the tile painted with the secondary button, if it doesn't erase
+    secondary_tile: Option<(usize, ui::Rect)>,
+
+    /// the size in pixels of the spritesheet image, once it has been read
+    sheet_dimensions: Option<(u32, u32)>,
+    /// true once the size of the spritesheet couldn't be read, so it isn't read again every frame until the sheet changes
+    sheet_unreadable: bool,
+    /// why the spritesheet of a loaded workspace couldn't be read, until it's relocated or dismissed
+    sheet_error: Option<String>,
+    /// when the spritesheet file had last been changed as of reading it, to notice it being edited elsewhere
+    sheet_modified: Option<std::time::SystemTime>,
+    /// true once the spritesheet file changed on disk, until it's reloaded or the change is ignored
+    sheet_changed: bool,
+    /// watches the spritesheet file while sheets are reloaded automatically
+    sheet_watcher: Option<watcher::SheetWatcher>,
+    /// the placed tiles whose index the swapped in sheet doesn't have, with that index
+    missing_tiles: Vec<(TilePos, usize)>,
+    /// the sheet decoded by hand for the path, filtering and color key it was made with,
+    /// once the image loaders failed on its uri or a color key is set, nothing in it if decoding failed too
+    fallback_texture: Option<FallbackTexture>,
+    /// the texture the tiles were last drawn with, as the loaders or the fallback resolved it,
+    /// the ids the loaders hand out depend on everything else they loaded before
+    sheet_texture_id: Option<ui::TextureId>,
+    /// sizes the palette to the panel, from when a new sheet is chosen until the scale is changed by hand
+    fit_palette: bool,
+    /// the rows and columns the uvs of the placed tiles were picked with
+    tile_slicing: Option<(usize, usize)>,
+    /// the tiles of the spritesheet without a single visible pixel,
+    /// along with the rows and columns they were found with
+    blank_tiles: Option<((usize, usize), HashSet<usize>)>,
+    /// the blank tiles being looked for on a background thread, along with the rows and columns they're looked for with
+    blank_tiles_search: Option<((usize, usize), std::sync::mpsc::Receiver<HashSet<usize>>)>,
+
+    /// drag state to check if we need to draw,
+    /// holds the tile and opacity each cell had before the stroke went over it
+    dragging: Option<indexmap::IndexMap<TilePos, (ui::Rect, u8)>>,
+    /// the button that started the current stroke and what it paints
+    stroke_brush: Option<(ui::PointerButton, ui::Rect)>,
+    /// the cells the current stroke went over, painted or not
+    touched_cells: HashSet<TilePos>,
+    /// the button dragging a shape, what it paints and the cell the drag started from
+    shape_drag: Option<(ui::PointerButton, ui::Rect, TilePos)>,
+
+    /// the rectangle of cells copy and cut act on
+    selection: Option<Selection>,
+    /// the cells picked for the tile they hold rather than for where they are, in place of the rectangle
+    mask: Option<HashSet<TilePos>>,
+    /// true while the selection is being dragged
+    selecting: bool,
+    /// the cell the move tool started dragging the selection from
+    moving: Option<TilePos>,
+    /// the cell an alt drag started capturing a stamp from
+    stamp_capture: Option<TilePos>,
+    /// the placed tiles an alt drag picked up, painted all at once in place of the selected tile
+    stamp: Option<clipboard::ClipboardTiles>,
+    /// the saved map the tiles are compared against, and how many operations had been made when they last were
+    diff: Option<(diff::MapDiff, usize)>,
+
+    /// the images of the palette page and of the page in the second pane,
+    /// rebuilt only when the slicing or the page changes
+    palette_images: [Vec<(usize, ui::Image<'static>)>; 2],
+    /// the rows, columns, filtering and tiles each pane's images were built for
+    palette_key: [(usize, usize, ui::TextureOptions, Vec<usize>); 2],
+
+    /// the indices of the last tiles picked, the most recent first
+    recent_tiles: std::collections::VecDeque<usize>,
+    /// the tiles ctrl clicked in the palette, what a shuffle swaps the selected tile for
+    variant_tiles: Vec<usize>,
+
+    /// the tiles we will actually draw
+    tiles: Tiles,
+    /// free text attached to cells, like gameplay markers, whether they hold a tile or not
+    notes: HashMap<TilePos, String>,
+    /// the points and areas placed over the map
+    objects: Vec<objects::MapObject>,
+    /// the alpha of the tiles drawn see-through, the cells missing from it are opaque
+    opacity: HashMap<TilePos, u8>,
+    /// the object shown in the object window
+    selected_object: Option<usize>,
+    /// the corner an area object is being dragged from
+    object_drag: Option<[f64; 2]>,
+
+    undos: TilesHistory,
+    redos: TilesHistory,
+    /// the cells the last undo or redo changed and when, highlighted for a moment
+    history_flash: Option<(Vec<TilePos>, std::time::Instant)>,
+    /// every edit made since the workspace was opened, kept for exporting
+    operations: Vec<oplog::Operation>,
+    /// true once the tiles, notes or objects changed since the workspace was last saved or opened
+    dirty: bool,
+
+    /// preferences restored along with the workspace
+    settings: settings::ProjectSettings
+}
+
 impl Application
 {
+    fn workspace(&self) -> &Workspace
+    {
+        &self.workspaces[self.active_workspace]
+    }
+
+    fn workspace_mut(&mut self) -> &mut Workspace
+    {
+        &mut self.workspaces[self.active_workspace]
+    }
+
+    /// shows `workspace` in a new tab, or in the current one if it's still untouched
+    fn open_workspace(&mut self, mut workspace: Workspace)
+    {
+        // the last selected tile comes back along with the spritesheet it was picked from
+        if let (None, Some(sprite_sheet), Some((path, idx))) =
+            (workspace.selected_tile, &workspace.sprite_sheet, &self.preferences.last_tile)
+        {
+            if *path == sprite_sheet.path && *idx < sprite_sheet.rows * sprite_sheet.columns
+            {
+                let uv = tile_uv(*idx, sprite_sheet.rows, sprite_sheet.columns);
+                workspace.select_tile(*idx, uv)
+            }
+        }
+
+        if self.workspace().is_untouched()
+        {
+            *self.workspace_mut() = workspace
+        }
+        else
+        {
+            self.workspaces.push(workspace);
+            self.active_workspace = self.workspaces.len() - 1
+        }
+    }
+
+    fn close_workspace(&mut self, idx: usize)
+    {
+        self.workspaces.remove(idx);
+
+        // an import started from the closed tab has nowhere to go anymore
+        self.map_import = self.map_import
+            .take()
+            .filter(|(_, import_workspace)| *import_workspace != idx)
+            .map(|(map_import, import_workspace)| if import_workspace > idx
+            {
+                (map_import, import_workspace - 1)
+            }
+            else
+            {
+                (map_import, import_workspace)
+            });
+
+        if self.workspaces.is_empty()
+        {
+            self.workspaces.push(Workspace::new())
+        }
+
+        if self.active_workspace > idx || self.active_workspace >= self.workspaces.len()
+        {
+            self.active_workspace = self.active_workspace.saturating_sub(1)
+        }
+    }
+
     fn top_panel(&mut self, app: &mut app::App)
     {
         let frame = ui::Frame
@@ -87,7 +798,7 @@ impl Application
 
         let contents = |ui: &mut ui::egui::Ui|
         {
-            let text_style = |text| ui::RichText::new(text)
+            let text_style = |text: &str| ui::RichText::new(text)
                 .size(15.)
                 .color(ui::Color32::from_gray(200));
 
@@ -108,6 +819,19 @@ impl Application
                             self.select_spritesheet()
                         }
 
+                        // for tilesets living in a shared asset repository
+                        if ui.button(text_style("new from url")).clicked()
+                        {
+                            self.sheet_url_entry = Some(String::new());
+                            ui.close_menu()
+                        }
+
+                        if ui.button(text_style("new from asset pack")).clicked()
+                        {
+                            self.open_asset_pack();
+                            ui.close_menu()
+                        }
+
                         // load a tilemap workspace
                         let load = ui.button
                         (
@@ -116,311 +840,3929 @@ impl Application
 
                         if load.clicked()
                         {
-                            let _ = self.load_workspace();
+                            self.load_workspace();
                         }
 
-                        // reset button
-                        let reset = ui.button(text_style("clear"));
-                    
-                        if reset.clicked() && !self.tiles.is_empty()
+                        // retheming a map with another tileset laid out the same way
+                        let swap = ui.add_enabled
+                        (
+                            self.workspace().sprite_sheet.is_some(),
+                            ui::Button::new(text_style("swap spritesheet"))
+                        );
+
+                        if swap.clicked()
                         {
-                            let tiles = self.tiles.clone();
-                            self.tiles.clear();
-                        
-                            self.undos.add(tiles);
+                            self.swap_spritesheet();
+                            ui.close_menu()
                         }
-                    })
-                }
-            )
-        };
 
-        ui::TopBottomPanel::top("path")
-            .frame(frame)
-            .show(app.ui().context(), contents);
-    }
+                        if ui.button(text_style("browse projects")).clicked()
+                        {
+                            self.browse_projects();
+                            ui.close_menu()
+                        }
 
-    fn select_spritesheet(&mut self)
-    {
-        if let Some(path) = rfd::FileDialog::new()
-            .add_filter("", &["png"])
-            .set_file_name("choose a spritesheet")
-            .pick_file()
-            {
-                self.sprite_sheet = Some(SpriteSheet { path, rows: 1, columns: 1 });
-                self.workspace_path = None
-            }
-    }
+                        // for migrating a whole project at once
+                        if ui.button(text_style("convert folder to json")).clicked()
+                        {
+                            self.convert_folder();
+                            ui.close_menu()
+                        }
 
-    fn bottom_panel(&mut self, app: &mut app::App)
-    {
-        ui::TopBottomPanel::bottom("assets")
-        .frame(ui::Frame
-        {
-            inner_margin: ui::Margin::symmetric(1., 5.),
-            fill: ui::Color32::from_gray(35),
-            ..Default::default()
-        })
-        .show(app.ui().context(), |ui|
-        {
-            let Some(SpriteSheet { ref path, ref mut rows, ref mut columns }) = self.sprite_sheet else
-            {
-                return
-            };
-    
-            ui.label(path.to_string_lossy());
+                        // rebuild the tiles from a flat png of a map
+                        let import = ui.add_enabled
+                        (
+                            self.workspace().sprite_sheet.is_some() && self.map_import.is_none(),
+                            ui::Button::new(text_style("import png map"))
+                        );
 
-            ui.separator();
-            
-            let scale = 100. * self.asset_preview_scale;
+                        if import.clicked()
+                        {
+                            self.import_map()
+                        }
 
-            let collapsable_contents = |ui: &mut ui::egui::Ui|
-            {
-                ui.group(|ui| ui.vertical(|ui|
-                {
-                    ui.add
-                    (
-                        ui::Slider::new(&mut self.asset_preview_scale, 0.3..=3.)
-                            .handle_shape(ui::style::HandleShape::Rect
-                            {
-                                aspect_ratio: 0.75
-                            })
-                            .trailing_fill(true)
-                            .show_value(false)
-                    );
+                        // render the map to an image
+                        let export = ui.add_enabled
+                        (
+                            self.workspace().sprite_sheet.is_some(),
+                            ui::Button::new(text_style("export png"))
+                        );
 
-                        ui.horizontal
+                        if export.clicked()
+                        {
+                            self.report("export the png", self.workspace().export_png())
+                        }
+
+                        // the tile indices as text, for engines that don't read our files
+                        let grid = ui.add_enabled
                         (
-                            |ui|
-                            {
-                                ui.label(ui::RichText::new("row").monospace());
-                                ui.add(ui::DragValue::new(rows));
-                            }
+                            self.workspace().sprite_sheet.is_some(),
+                            ui::Button::new(text_style("export index grid"))
                         );
-                        
-                        ui.horizontal
+
+                        if grid.clicked()
+                        {
+                            self.show_grid_export = true;
+                            ui.close_menu()
+                        }
+
+                        // where agents can go, for the pathfinding of the game
+                        let walkable = ui.add_enabled
                         (
-                            |ui|
-                            {
-                                ui.label(ui::RichText::new("columns").monospace());
-                                ui.add(ui::DragValue::new(columns));
-                            }
+                            self.workspace().sprite_sheet.is_some(),
+                            ui::Button::new(text_style("export walkable grid"))
                         );
-                }));
 
-                let style = ui.style_mut();
+                        if walkable.clicked()
+                        {
+                            self.show_walk_export = true;
+                            ui.close_menu()
+                        }
 
-                style.spacing.button_padding = (0.1, 0.1).into();
-                style.spacing.item_spacing = (2.5, 2.5).into();
+                        // small levels can live straight in the game's code
+                        let rust = ui.add_enabled
+                        (
+                            self.workspace().sprite_sheet.is_some(),
+                            ui::Button::new(text_style("export rust array"))
+                        );
 
-                style.visuals.widgets.hovered.bg_stroke = ui::Stroke::new(2.5, ui::Color32::LIGHT_GRAY);
-                style.visuals.selection.stroke = ui::Stroke::new(5., ui::Color32::LIGHT_GRAY);
+                        if rust.clicked()
+                        {
+                            self.report("export the rust array", self.workspace().export_rust())
+                        }
 
-                let uri = "file://".to_owned() + path
-                    .to_str()
-                    .expect
-                    (
-                        "received invalid UTF-8, why not just use ostr as source anyway?"
-                    );
+                        // a tileset and a scene with a tilemap that godot opens as they are
+                        let godot = ui.add_enabled
+                        (
+                            self.workspace().sprite_sheet.is_some(),
+                            ui::Button::new(text_style("export godot tilemap"))
+                        );
 
-                for (idx, image) in load_images(uri, *rows, *columns).enumerate()
-                {
-                    let selected = self.selected_tile
-                        .is_some_and(|(sel_idx, ..)| idx == sel_idx);
-                    
-                    let uv = image.image_options().uv;
+                        if godot.clicked()
+                        {
+                            self.report("export the godot tilemap", self.workspace().export_godot())
+                        }
 
-                    let tile_display = ui.add_sized
-                    (
-                        (scale,scale),
-                        ui::Button::image(image)
-                            .fill(ui::Color32::TRANSPARENT)
-                            .selected(selected)
+                        // the objects as a tiled object layer, lined up with the index grid
+                        let objects = ui.add_enabled
+                        (
+                            self.workspace().sprite_sheet.is_some() && !self.workspace().objects.is_empty(),
+                            ui::Button::new(text_style("export objects"))
+                        );
 
-                    );
+                        if objects.clicked()
+                        {
+                            self.report("export the objects", self.workspace().export_objects())
+                        }
 
-                    if tile_display.clicked()
-                    {
-                        self.selected_tile = Some((idx,uv))
-                    }
-                }
-            };
+                        // describe the slicing so engines can reproduce it
+                        let manifest = ui.add_enabled
+                        (
+                            self.workspace().sprite_sheet.is_some(),
+                            ui::Button::new(text_style("export slicing manifest"))
+                        );
 
-            let header_text = ui::RichText::new("tiles")
-                .size(15.)
-                .monospace()
-                .color(ui::Color32::from_gray(100));
+                        if manifest.clicked()
+                        {
+                            self.report("export the slicing manifest", self.workspace().export_manifest())
+                        }
 
-            
+                        // the edits made so far, to make them again somewhere else
+                        let log = ui.add_enabled
+                        (
+                            !self.workspace().operations.is_empty(),
+                            ui::Button::new(text_style("export operation log"))
+                        );
 
-            ui::CollapsingHeader::new(header_text)
-                .default_open(true)
-                .show(ui, |ui| ui.horizontal_wrapped(collapsable_contents));
-        });
-    }
+                        if log.clicked()
+                        {
+                            self.report("export the operation log", self.workspace().export_operation_log())
+                        }
 
-    fn editor_grid(&mut self, app: &mut app::App)
-    {
-        let plot_contents = |ui: &mut plot::PlotUi|
-        {
-            ui.vline(plot::VLine::new(0.).color(ui::Color32::GRAY));
-            ui.hline(plot::HLine::new(0.).color(ui::Color32::GRAY));
+                        if ui.button(text_style("replay operation log")).clicked()
+                        {
+                            let replayed = self.replay_operation_log();
+                            self.report("replay the operation log", replayed)
+                        }
 
-            // use the middle click instead of left click
-            if ui.response().dragged_by(ui::PointerButton::Middle)
-            {
-                ui.ctx().set_cursor_icon(ui::CursorIcon::Grabbing);
-                ui.translate_bounds(-ui.pointer_coordinate_drag_delta())
-            }
+                        // what changed since another save, like a code review would show it
+                        if ui.button(text_style("compare with saved map")).clicked()
+                        {
+                            self.compare_with_saved();
+                            ui.close_menu()
+                        }
 
-            if let Some(screen_pos) = ui.response().hover_pos()
-            {
-                let mut pos = ui.plot_from_screen(screen_pos);
+                        // reset buttons, either emptying the map or covering what's on screen
+                        ui.menu_button(text_style("clear"), |ui|
+                        {
+                            let visible_cells = self.visible_cells;
+                            let workspace = self.workspace_mut();
 
-                let floor_pos = plot::PlotPoint { x: pos.x.floor(), y: pos.y.floor() };
+                            let empty = ui.add_enabled
+                            (
+                                !workspace.tiles.is_empty(),
+                                ui::Button::new(text_style("empty"))
+                            );
+                        
+                            if empty.clicked()
+                            {
+                                workspace.clear();
+                                ui.close_menu()
+                            }
 
-                pos.x = floor_pos.x.floor() + 0.5;
-                pos.y = floor_pos.y.floor() + 0.5;
-    
-                let response = ui.response();
-                
-                // this means we have no tile selected to draw,
-                // meaning we don't need to draw anything the on tiles
-                // so we just return
-                let Some((.., selected_uv)) = self.selected_tile else
-                {
-                    return
-                };
-    
-                if response.drag_started_by(ui::PointerButton::Primary)
-                {
-                    self.redos.clear();
-                    self.dragging = Some(indexmap::IndexMap::with_capacity(8))
-                }
-                else if response.drag_released_by(ui::PointerButton::Primary)
-                {
-                    self.undos.add(self.dragging.take().unwrap())
-                }
+                            let fill = ui.add_enabled
+                            (
+                                workspace.selected_tile.is_some() && visible_cells.is_some(),
+                                ui::Button::new(text_style("fill visible with selected tile"))
+                            );
 
-                if let Some(ref mut current_edit_tiles) = self.dragging
-                {
-                    let pos = TilePos
+                            if fill.clicked()
+                            {
+                                if let (Some((.., uv)), Some(visible)) = (workspace.selected_tile, visible_cells)
+                                {
+                                    workspace.fill(visible, uv)
+                                }
+
+                                ui.close_menu()
+                            }
+                        });
+                    });
+
+                    ui.menu_button(text_style("edit"), |ui|
                     {
-                        x: floor_pos.x as i32,
-                        y: floor_pos.y as i32
-                    };
+                        let workspace = self.workspace_mut();
+                        let depth = workspace.settings.history_depth;
+
+                        let undo = ui.add_enabled
+                        (
+                            !workspace.undos.is_empty(),
+                            ui::Button::new(text_style(&format!("undo ({})", workspace.undos.len())))
+                                .shortcut_text("ctrl+z")
+                        )
+                        .on_hover_text(format!("{} of {depth} steps kept", workspace.undos.len()));
+
+                        if undo.clicked()
+                        {
+                            workspace.undo()
+                        }
+
+                        let redo = ui.add_enabled
+                        (
+                            !workspace.redos.is_empty(),
+                            ui::Button::new(text_style(&format!("redo ({})", workspace.redos.len())))
+                                .shortcut_text("ctrl+shift+z")
+                        )
+                        .on_hover_text(format!("{} of {depth} steps kept", workspace.redos.len()));
+
+                        if redo.clicked()
+                        {
+                            workspace.redo()
+                        }
+                    });
 
-                    if current_edit_tiles.get(&pos).is_none()
+                    ui.menu_button(text_style("brush"), |ui|
                     {
-                        match self.tiles.insert(pos, selected_uv)
+                        ui.horizontal(|ui|
                         {
-                            Some(old_uv) =>
+                            ui.label(ui::RichText::new("density").monospace());
+                            ui.add
+                            (
+                                ui::Slider::new(&mut self.brush_density, 0. ..=1.)
+                                    .custom_formatter(|density, _| format!("{:.0}%", density * 100.))
+                            );
+                        });
+
+                        ui.horizontal(|ui|
+                        {
+                            ui.label(ui::RichText::new("size").monospace());
+                            ui.add(ui::DragValue::new(&mut self.max_brush_size).clamp_range(1..=16).suffix(" cells"))
+                                .on_hover_text("the size at full pressure, a pen pressing lightly paints smaller");
+                        });
+
+                        ui.horizontal(|ui|
+                        {
+                            ui.label(ui::RichText::new("opacity").monospace());
+                            ui.add
+                            (
+                                ui::Slider::new(&mut self.paint_opacity, 0. ..=1.)
+                                    .custom_formatter(|opacity, _| format!("{:.0}%", opacity * 100.))
+                            )
+                            .on_hover_text("draws the painted tiles see-through, for fog or ghostly ledges");
+                        });
+
+                        ui.checkbox(&mut self.fill_across_variants, text_style("fill across variants"))
+                            .on_hover_text("a fill started on a ctrl clicked variant covers the cells holding any of them");
+
+                        ui.separator();
+
+                        let mut mirror = self.symmetry.is_some();
+
+                        if ui.checkbox(&mut mirror, text_style("mirror strokes"))
+                            .on_hover_text("paints and erases on both sides of a line of the grid")
+                            .changed()
+                        {
+                            self.symmetry = mirror.then_some(Symmetry { horizontal: false, half_cells: 0 })
+                        }
+
+                        if let Some(symmetry) = &mut self.symmetry
+                        {
+                            ui.horizontal(|ui|
                             {
-                                current_edit_tiles.insert(pos, old_uv);
-                            }
-                            None =>
+                                ui.radio_value(&mut symmetry.horizontal, false, text_style("left and right"));
+                                ui.radio_value(&mut symmetry.horizontal, true, text_style("up and down"));
+                            });
+
+                            ui.horizontal(|ui|
                             {
-                                current_edit_tiles.insert(pos, ui::Rect::NOTHING);
-                            }
+                                ui.label(ui::RichText::new("line at").monospace());
+                                ui.add
+                                (
+                                    ui::DragValue::new(&mut symmetry.half_cells)
+                                        .custom_formatter(|half_cells, _| format!("{}", half_cells / 2.))
+                                        .custom_parser(|text| text.parse::<f64>().ok().map(|cells| (cells * 2.).round()))
+                                )
+                                .on_hover_text("in cells from the origin, halves go through the middle of a cell");
+                            });
                         }
-                    }
-                }
 
-                ui.image
-                (
-                    plot::PlotImage::new(ui::TextureId::Managed(1),
-                    pos, (1., 1.)
-                )
-                    .highlight(true)
-                    .uv(selected_uv));
-            }
+                        ui.horizontal(|ui|
+                        {
+                            ui.label(ui::RichText::new("paint from").monospace());
+                            ui.add
+                            (
+                                ui::DragValue::new(&mut self.min_cell_pixels)
+                                    .clamp_range(0. ..=64.)
+                                    .suffix(" px per tile")
+                            )
+                            .on_hover_text("below this zoom the canvas can't be painted on");
+                        });
 
-            draw_tiles(&mut self.tiles, ui);
-            
-            fn draw_tiles (tiles: &mut Tiles, ui: &mut plot::PlotUi)
-            {
-                for (TilePos { x, y }, uv) in tiles
-                {
-                    ui.image(plot::PlotImage::new
-                    (
-                        ui::TextureId::Managed(1),
-                        plot::PlotPoint { x: *x as f64 + 0.5, y: *y as f64 + 0.5 },
-                        (1., 1.)
-                    )
-                    .uv(*uv))
-                }
-            }
-        };
+                        ui.label(ui::RichText::new("shape").monospace());
+                        ui.horizontal(|ui|
+                        {
+                            ui.radio_value(&mut self.brush_shape, BrushShape::Freehand, "freehand");
+                            ui.radio_value(&mut self.brush_shape, BrushShape::Rectangle, "rectangle");
+                            ui.radio_value(&mut self.brush_shape, BrushShape::Ellipse, "ellipse");
+                        });
 
-        let panel_contents = |ui: &mut ui::egui::Ui|
-        {
-            plot::Plot::new("tilemap display")
-                .data_aspect(1.)
+                        ui.horizontal(|ui|
+                        {
+                            ui.checkbox(&mut self.hollow_shapes, "hollow");
 
-                .x_grid_spacer(plot::log_grid_spacer(1))
-                .y_grid_spacer(plot::log_grid_spacer(1))
-            
-                .allow_double_click_reset(false)
-                
-                .allow_drag(false)
-                .allow_boxed_zoom(false)
-                .show_background(false)
-                
-                .show(ui, plot_contents)
+                            ui.add_enabled
+                            (
+                                self.hollow_shapes,
+                                ui::DragValue::new(&mut self.shape_border)
+                                    .clamp_range(1..=16)
+                                    .suffix(" cells thick")
+                            );
+                        });
+
+                        ui.separator();
+
+                        ui.checkbox(&mut self.magnetic_placement, "magnetic placement")
+                            .on_hover_text("near placed tiles the brush snaps right next to them");
+
+                        ui.horizontal(|ui|
+                        {
+                            ui.checkbox(&mut self.snap_to_super_grid, "snap to super-grid")
+                                .on_hover_text("paints whole blocks of cells and lines stamps up with them");
+
+                            ui.add(ui::DragValue::new(&mut self.super_grid_size).clamp_range(2..=64).suffix(" cells"));
+                        });
+
+                        ui.separator();
+
+                        ui.label(ui::RichText::new("paint over").monospace());
+                        ui.horizontal(|ui|
+                        {
+                            ui.radio_value(&mut self.paint_mode, PaintMode::Replace, "every cell");
+                            ui.radio_value(&mut self.paint_mode, PaintMode::OnlyEmpty, "only empty cells");
+                            ui.radio_value(&mut self.paint_mode, PaintMode::OnlyOccupied, "only placed tiles");
+                        });
+
+                        ui.separator();
+
+                        ui.label(ui::RichText::new("paste anchor").monospace());
+                        ui.horizontal(|ui|
+                        {
+                            ui.radio_value(&mut self.stamp_anchor, Anchor::TopLeft, "top left");
+                            ui.radio_value(&mut self.stamp_anchor, Anchor::Center, "center");
+                            ui.radio_value(&mut self.stamp_anchor, Anchor::BottomCenter, "bottom center");
+                        });
+
+                        ui.separator();
+
+                        ui.label(ui::RichText::new("scroll through recent tiles with").monospace());
+                        ui.horizontal(|ui|
+                        {
+                            ui.radio_value(&mut self.tile_cycle_modifier, ScrollModifier::Alt, "alt");
+                            ui.radio_value(&mut self.tile_cycle_modifier, ScrollModifier::Shift, "shift");
+                            ui.radio_value(&mut self.tile_cycle_modifier, ScrollModifier::Ctrl, "ctrl")
+                                .on_hover_text("replaces zooming with ctrl");
+                        });
+
+                        ui.separator();
+
+                        ui.label(ui::RichText::new("secondary button").monospace());
+                        ui.radio_value(&mut self.secondary_button, SecondaryButton::Erase, "erases");
+                        ui.radio_value
+                        (
+                            &mut self.secondary_button,
+                            SecondaryButton::PaintSecondaryTile,
+                            "paints the tile right clicked in the palette"
+                        );
+                    });
+
+                    ui.menu_button(text_style("view"), |ui|
+                    {
+                        ui.checkbox(&mut self.show_tile_outlines, text_style("tile outlines"));
+                        ui.checkbox(&mut self.show_coordinates, text_style("cell coordinates"));
+                        ui.checkbox(&mut self.show_crosshair, text_style("crosshair"))
+                            .on_hover_text("lines up tiles far apart on the same row or column");
+                        ui.checkbox(&mut self.show_palette_indices, text_style("palette tile indices"))
+                            .on_hover_text("shows the number of the hovered palette tile, the one the tile index popup takes");
+                        ui.checkbox(&mut self.show_stats, text_style("stats"));
+                        ui.checkbox(&mut self.show_texture_debug, text_style("texture debug"))
+                            .on_hover_text("shows the id of the texture the tiles are drawn with and where it came from");
+
+                        if ui.button(text_style("preview")).on_hover_text("tab").clicked()
+                        {
+                            self.toggle_preview();
+                            ui.close_menu()
+                        }
+                    });
+
+                    ui.menu_button(text_style("settings"), |ui|
+                    {
+                        let settings = &mut self.workspace_mut().settings;
+
+                        ui.label(ui::RichText::new("saved with this workspace").weak());
+
+                        ui.horizontal(|ui|
+                        {
+                            ui.label(ui::RichText::new("background").monospace());
+                            ui.color_edit_button_srgba(&mut settings.background);
+                        });
+
+                        ui.horizontal(|ui|
+                        {
+                            ui.label(ui::RichText::new("axes").monospace());
+                            ui.color_edit_button_srgba(&mut settings.axis_color);
+                        });
+
+                        ui.checkbox(&mut settings.grid_per_tile, text_style("one grid line per tile"))
+                            .on_hover_text("keeps the grid on the cells at every zoom");
+
+                        let depth = ui.horizontal(|ui|
+                        {
+                            ui.label(ui::RichText::new("history depth").monospace());
+                            ui.add(ui::DragValue::new(&mut settings.history_depth).clamp_range(1..=1000))
+                        }).inner;
+
+                        if depth.changed()
+                        {
+                            let workspace = self.workspace_mut();
+                            let depth = workspace.settings.history_depth;
+
+                            workspace.undos.set_depth(depth);
+                            workspace.redos.set_depth(depth)
+                        }
+
+                        let settings = &mut self.workspace_mut().settings;
+
+                        ui.horizontal(|ui|
+                        {
+                            ui.label(ui::RichText::new("backups").monospace());
+                            ui.add(ui::DragValue::new(&mut settings.backups).clamp_range(0..=MAX_BACKUPS))
+                        })
+                        .response
+                        .on_hover_text("how many earlier saves are kept as numbered files next to the workspace");
+
+                        ui.horizontal(|ui|
+                        {
+                            ui.label(ui::RichText::new("coordinate limit").monospace());
+                            ui.add(ui::DragValue::new(&mut settings.max_coordinate).clamp_range(1..=i32::MAX))
+                        })
+                        .response
+                        .on_hover_text("tiles can't be placed further than this from the origin");
+
+                        ui.horizontal(|ui|
+                        {
+                            ui.label(ui::RichText::new("past the limit").monospace());
+                            ui.radio_value(&mut settings.overflow, settings::OverflowPolicy::Clip, "clip")
+                                .on_hover_text("places what fits");
+                            ui.radio_value(&mut settings.overflow, settings::OverflowPolicy::Reject, "reject")
+                                .on_hover_text("places nothing of a brush, stamp, paste or fill that doesn't fit whole");
+                            ui.radio_value(&mut settings.overflow, settings::OverflowPolicy::Expand, "expand")
+                                .on_hover_text("raises the limit to fit it");
+                        });
+
+                        ui.horizontal(|ui|
+                        {
+                            ui.label(ui::RichText::new("origin offset").monospace());
+                            ui.add(ui::DragValue::new(&mut settings.origin_offset.0).prefix("x "));
+                            ui.add(ui::DragValue::new(&mut settings.origin_offset.1).prefix("y "))
+                        })
+                        .response
+                        .on_hover_text("the coordinates the axes and the exports give the cell at the origin");
+
+                        ui.separator();
+
+                        ui.label(ui::RichText::new("for every workspace").weak());
+
+                        ui.checkbox(&mut self.auto_reload_sheet, text_style("reload spritesheets when saved"))
+                            .on_hover_text("picks up the art saved from another program, keeping the tiles, the selection and the view");
+                    });
+
+                    ui.separator();
+
+                    self.tab_bar(ui)
+                }
+            )
         };
 
-        ui::CentralPanel::default()
-            .frame(ui::Frame
-            {
-                inner_margin: ui::Margin::symmetric(1., 5.),
-                fill: ui::Color32::from_gray(45),
-                ..Default::default()
-            })
-            .show(app.ui().context(), panel_contents);
+        ui::TopBottomPanel::top("path")
+            .frame(frame)
+            .show(app.ui().context(), contents);
     }
 
-    fn check_input(&mut self, app: &mut app::App)
+    fn tab_bar(&mut self, ui: &mut ui::egui::Ui)
     {
-        if app.input.get_key_down(input::KeyCode::KeyZ)
-            && app.input.get_key_holding
-            (
-                input::KeyCode::ControlLeft
-            )
-            && !app.input.get_key_holding
-            (
-                input::KeyCode::ShiftLeft
-            )
+        let mut close = None;
+
+        for (idx, workspace) in self.workspaces.iter().enumerate()
         {
-            let Some(undo_tiles) = self.undos.pop() else 
+            let title = if workspace.dirty
             {
-                return
+                format!("{} *", workspace.title())
+            }
+            else
+            {
+                workspace.title()
             };
 
-            // here we will gather the tiles we are replacing with the undo tiles,
-            // so that we can use them as redo operation later
-            let mut redo_tiles = IndexMap::with_capacity(undo_tiles.len());
+            let tab = ui.selectable_label(idx == self.active_workspace, ui::RichText::new(title).monospace());
 
-            for (pos, uv) in undo_tiles
+            if tab.clicked()
             {
-                if uv == ui::Rect::NOTHING
-                {
-                    match self.tiles.remove(&pos)
-                    {
-                        Some(old_uv) => redo_tiles.insert(pos, old_uv),
-                        None => redo_tiles.insert(pos, ui::Rect::NOTHING)
-                    };
-                }
-                else
-                {
-                    match self.tiles.insert(pos, uv)
-                    {
-                        Some(old_uv) => redo_tiles.insert(pos, old_uv),
-                        None => redo_tiles.insert(pos, ui::Rect::NOTHING)
-                    };
-                }
+                self.active_workspace = idx
             }
 
-            self.redos.add(redo_tiles)
-        }
+            if ui.small_button("x").on_hover_text("close tab").clicked()
+            {
+                close = Some(idx)
+            }
+        }
+
+        // unsaved changes are only thrown away once that's confirmed
+        match close
+        {
+            Some(idx) if self.workspaces[idx].dirty => self.closing_workspace = Some(idx),
+            Some(idx) => self.close_workspace(idx),
+            None => ()
+        }
+    }
+
+    /// asks what to do with the unsaved changes of a tab being closed
+    fn close_workspace_window(&mut self, app: &mut app::App)
+    {
+        let Some(idx) = self.closing_workspace.filter(|idx| *idx < self.workspaces.len()) else
+        {
+            self.closing_workspace = None;
+            return
+        };
+
+        let (mut save, mut discard, mut cancel) = (false, false, false);
+
+        ui::Window::new("unsaved changes")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(ui::Align2::CENTER_CENTER, (0., 0.))
+            .show(app.ui().context(), |ui|
+            {
+                ui.label(format!("{} has changes that weren't saved", self.workspaces[idx].title()));
+
+                ui.horizontal(|ui|
+                {
+                    save = ui.button("save and close").clicked();
+                    discard = ui.button("close without saving").clicked();
+                    cancel = ui.button("cancel").clicked();
+                });
+            });
+
+        if save
+        {
+            self.active_workspace = idx;
+            self.save_workspace();
+
+            // a save dialog that was cancelled keeps the tab open
+            discard = !self.workspaces[idx].dirty
+        }
+
+        if discard
+        {
+            self.close_workspace(idx)
+        }
+
+        if save || discard || cancel
+        {
+            self.closing_workspace = None
+        }
+    }
+
+    fn select_spritesheet(&mut self)
+    {
+        if let Some(path) = file_dialog(self.sheet_folder.as_deref())
+            .add_filter("", &["png"])
+            .set_file_name("choose a spritesheet")
+            .pick_file()
+            {
+                self.sheet_folder = path.parent().map(PathBuf::from);
+                self.open_spritesheet(path)
+            }
+    }
+
+    /// puts another spritesheet under the tiles of the current workspace
+    fn swap_spritesheet(&mut self)
+    {
+        if let Some(path) = file_dialog(self.sheet_folder.as_deref())
+            .add_filter("", &["png"])
+            .set_file_name("choose the new spritesheet")
+            .pick_file()
+        {
+            self.sheet_folder = path.parent().map(PathBuf::from);
+            self.workspace_mut().swap_sheet(path)
+        }
+    }
+
+    /// opens a saved workspace, or starts a new one from a spritesheet, going by the extension of `path`
+    fn open_file(&mut self, path: PathBuf)
+    {
+        let extension = path
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_lowercase());
+
+        match extension.as_deref()
+        {
+            Some("bag") =>
+            {
+                self.workspace_folder = path.parent().map(PathBuf::from);
+                self.open_saved(path);
+            }
+            Some("png") =>
+            {
+                self.sheet_folder = path.parent().map(PathBuf::from);
+                self.open_spritesheet(path)
+            }
+            _ => ()
+        }
+    }
+
+    /// starts a new workspace painting with the spritesheet at `path`
+    fn open_spritesheet(&mut self, path: PathBuf)
+    {
+        let mut workspace = Workspace::new();
+        workspace.sprite_sheet = Some(SpriteSheet { path, rows: 1, columns: 1 });
+        workspace.fit_palette = true;
+
+        self.open_workspace(workspace)
+    }
+
+    /// compares the current map against the tiles of a chosen `.bag` file
+    fn compare_with_saved(&mut self)
+    {
+        let Some(path) = file_dialog(self.workspace_folder.as_deref())
+            .add_filter("", &["bag"])
+            .set_file_name("compare with")
+            .pick_file()
+        else
+        {
+            return
+        };
+
+        let workspace = self.workspace_mut();
+
+        match diff::MapDiff::load(path, &workspace.tiles)
+        {
+            Ok(diff) => workspace.diff = Some((diff, workspace.operations.len())),
+            Err(err) => self.toast = Some((format!("couldn't compare: {err}"), std::time::Instant::now()))
+        }
+    }
+
+    /// sums up the comparison with a saved map, the canvas shows it cell by cell while it's open
+    fn diff_window(&mut self, app: &mut app::App)
+    {
+        let workspace = &mut self.workspaces[self.active_workspace];
+
+        let Some((ref mut diff, ref mut compared)) = workspace.diff else
+        {
+            return
+        };
+
+        // every edit, undo and redo is logged, so a longer log means the tiles changed
+        if *compared != workspace.operations.len()
+        {
+            diff.compare(&workspace.tiles);
+            *compared = workspace.operations.len()
+        }
+
+        let mut open = true;
+        let mut export = false;
+
+        ui::Window::new("compared with saved map")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(app.ui().context(), |ui|
+            {
+                ui.label(ui::RichText::new(diff.base_path.to_string_lossy()).weak());
+                ui.label(diff.summary());
+
+                ui.horizontal(|ui|
+                {
+                    for (name, color) in [("added", DIFF_ADDED), ("removed", DIFF_REMOVED), ("changed", DIFF_CHANGED)]
+                    {
+                        ui.label(ui::RichText::new(name).color(color));
+                    }
+                });
+
+                export = ui.add_enabled(workspace.sprite_sheet.is_some(), ui::Button::new("export json")).clicked();
+            });
+
+        let exported = export.then(|| workspace.export_diff());
+
+        if !open
+        {
+            workspace.diff = None
+        }
+
+        if let Some(exported) = exported
+        {
+            self.report("export the comparison", exported)
+        }
+    }
+
+    /// opens a new tab holding the map rebuilt from a chosen operation log
+    fn replay_operation_log(&mut self) -> std::io::Result<()>
+    {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("", &["json"])
+            .set_file_name("choose an operation log")
+            .pick_file()
+        else
+        {
+            return Ok(())
+        };
+
+        let workspace = oplog::replay(&path)?;
+        self.open_workspace(workspace);
+
+        Ok(())
+    }
+
+    fn import_map(&mut self)
+    {
+        let workspace = &self.workspaces[self.active_workspace];
+
+        let Some(ref sprite_sheet) = workspace.sprite_sheet else
+        {
+            return
+        };
+
+        if let Some(path) = rfd::FileDialog::new()
+            .add_filter("", &["png"])
+            .set_file_name("choose a map to import")
+            .pick_file()
+            {
+                self.unmatched_cells = None;
+
+                let map_import = import::MapImport::start(path, sprite_sheet, workspace.settings.color_key);
+                self.map_import = Some((map_import, self.active_workspace))
+            }
+    }
+
+    /// shows the progress of a running import and reports the cells it couldn't match
+    fn import_window(&mut self, app: &mut app::App)
+    {
+        if let Some((ref map_import, import_workspace)) = self.map_import
+        {
+            match map_import.poll()
+            {
+                Some(Ok(import::ImportedMap { tiles, unmatched })) =>
+                {
+                    // the tiles were matched against a slicing the tab might not use anymore
+                    let workspace = &mut self.workspaces[import_workspace];
+
+                    let same_slicing = workspace.sprite_sheet.as_ref().is_some_and(|sprite_sheet|
+                        sprite_sheet.path == map_import.sprite_sheet.path
+                        && sprite_sheet.rows == map_import.sprite_sheet.rows
+                        && sprite_sheet.columns == map_import.sprite_sheet.columns
+                    );
+
+                    if same_slicing
+                    {
+                        workspace.replace_tiles(tiles.into_iter().collect());
+                        self.unmatched_cells = Some(unmatched)
+                    }
+                    else
+                    {
+                        let message = "the spritesheet changed during the import, import the map again";
+                        self.toast = Some((message.to_owned(), std::time::Instant::now()))
+                    }
+
+                    self.map_import = None
+                }
+                Some(Err(err)) =>
+                {
+                    self.toast = Some((format!("couldn't import the map: {err}"), std::time::Instant::now()));
+                    self.map_import = None
+                }
+                None =>
+                {
+                    let progress = map_import.progress();
+
+                    ui::Window::new("importing map")
+                        .collapsible(false)
+                        .resizable(false)
+                        .show(app.ui().context(), |ui|
+                        {
+                            ui.add(ui::ProgressBar::new(progress).show_percentage())
+                        });
+
+                    app.ui().context().request_repaint()
+                }
+            }
+        }
+
+        let mut open = self.unmatched_cells.is_some();
+
+        if let Some(ref unmatched) = self.unmatched_cells
+        {
+            ui::Window::new("import report")
+                .open(&mut open)
+                .collapsible(false)
+                .show(app.ui().context(), |ui|
+                {
+                    if unmatched.is_empty()
+                    {
+                        ui.label("every cell matched a tile");
+                        return
+                    }
+
+                    ui.label(format!("{} cells didn't match any tile:", unmatched.len()));
+
+                    ui::ScrollArea::vertical()
+                        .max_height(200.)
+                        .show(ui, |ui|
+                        {
+                            for TilePos { x, y } in unmatched
+                            {
+                                ui.label(ui::RichText::new(format!("{x}, {y}")).monospace());
+                            }
+                        });
+                });
+        }
+
+        if !open
+        {
+            self.unmatched_cells = None
+        }
+    }
+
+    /// a small popup to select a tile by typing its index
+    fn tile_index_window(&mut self, app: &mut app::App)
+    {
+        let Some(ref mut entry) = self.tile_index_entry else
+        {
+            return
+        };
+
+        let workspace = &mut self.workspaces[self.active_workspace];
+
+        let tile_count = workspace.sprite_sheet
+            .as_ref()
+            .map_or(0, |sprite_sheet| sprite_sheet.rows * sprite_sheet.columns);
+
+        let mut done = false;
+
+        ui::Window::new("select tile")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(ui::Align2::CENTER_TOP, (0., 40.))
+            .show(app.ui().context(), |ui|
+            {
+                ui.text_edit_singleline(entry).request_focus();
+
+                // only accept indices that exist in the spritesheet
+                let idx = entry.trim().parse::<usize>().ok().filter(|idx| *idx < tile_count);
+
+                match idx
+                {
+                    Some(_) => ui.label(ui::RichText::new("enter to select").weak()),
+                    None => ui.label(ui::RichText::new(format!("type an index from 0 to {}", tile_count.saturating_sub(1))).weak())
+                };
+
+                if ui.input(|input| input.key_pressed(ui::Key::Enter))
+                {
+                    if let (Some(idx), Some(sprite_sheet)) = (idx, &workspace.sprite_sheet)
+                    {
+                        workspace.select_tile(idx, tile_uv(idx, sprite_sheet.rows, sprite_sheet.columns));
+                        done = true
+                    }
+                }
+
+                if ui.input(|input| input.key_pressed(ui::Key::Escape))
+                {
+                    done = true
+                }
+            });
+
+        if done
+        {
+            self.tile_index_entry = None
+        }
+    }
+
+    /// asks for the url of a spritesheet, which is then downloaded in the background
+    fn sheet_url_window(&mut self, app: &mut app::App)
+    {
+        let Some(ref mut entry) = self.sheet_url_entry else
+        {
+            return
+        };
+
+        let (mut download, mut done) = (false, false);
+
+        ui::Window::new("spritesheet from url")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(ui::Align2::CENTER_TOP, (0., 40.))
+            .show(app.ui().context(), |ui|
+            {
+                ui.add(ui::TextEdit::singleline(entry).hint_text("https://").desired_width(360.)).request_focus();
+                ui.label(ui::RichText::new("enter to download, it's kept in a cache so saved maps find it again").weak());
+
+                download = ui.input(|input| input.key_pressed(ui::Key::Enter));
+                done = ui.input(|input| input.key_pressed(ui::Key::Escape));
+            });
+
+        if download
+        {
+            self.sheet_download = Some(download::SheetDownload::start(entry.trim().to_owned()));
+            done = true
+        }
+
+        if done
+        {
+            self.sheet_url_entry = None
+        }
+    }
+
+    /// waits on a spritesheet download, opening it in a new tab once it's there
+    fn sheet_download_window(&mut self, app: &mut app::App)
+    {
+        let Some(ref sheet_download) = self.sheet_download else
+        {
+            return
+        };
+
+        match sheet_download.poll()
+        {
+            Some(Ok(path)) =>
+            {
+                self.sheet_download = None;
+                self.open_spritesheet(path)
+            }
+            Some(Err(err)) =>
+            {
+                self.toast = Some((format!("couldn't download the spritesheet: {err}"), std::time::Instant::now()));
+                self.sheet_download = None
+            }
+            None =>
+            {
+                let mut cancel = false;
+
+                ui::Window::new("downloading spritesheet")
+                    .collapsible(false)
+                    .resizable(false)
+                    .show(app.ui().context(), |ui|
+                    {
+                        ui.horizontal(|ui|
+                        {
+                            ui.spinner();
+                            ui.label(&sheet_download.url);
+                        });
+
+                        cancel = ui.button("cancel").clicked();
+                    });
+
+                // the thread finishes on its own, its result just goes nowhere
+                if cancel
+                {
+                    self.sheet_download = None
+                }
+
+                app.ui().context().request_repaint_after(std::time::Duration::from_millis(100))
+            }
+        }
+    }
+
+    /// asks for a zip asset pack and lists the images in it to pick a spritesheet from
+    fn open_asset_pack(&mut self)
+    {
+        let Some(path) = file_dialog(self.sheet_folder.as_deref())
+            .add_filter("", &["zip"])
+            .set_file_name("choose an asset pack")
+            .pick_file()
+        else
+        {
+            return
+        };
+
+        match download::pack_images(&path)
+        {
+            Ok(images) if images.is_empty() =>
+            {
+                self.toast = Some(("the asset pack holds no png images".to_owned(), std::time::Instant::now()))
+            }
+            Ok(images) => self.asset_pack = Some((path, images)),
+            Err(err) => self.toast = Some((format!("couldn't open the asset pack: {err}"), std::time::Instant::now()))
+        }
+    }
+
+    /// lists the images of the open asset pack, the one clicked is copied into the cache and opened
+    fn asset_pack_window(&mut self, app: &mut app::App)
+    {
+        let Some((ref pack, ref images)) = self.asset_pack else
+        {
+            return
+        };
+
+        let mut open = true;
+        let mut picked = None;
+
+        ui::Window::new("asset pack")
+            .open(&mut open)
+            .collapsible(false)
+            .show(app.ui().context(), |ui|
+            {
+                ui.label(ui::RichText::new(pack.file_name().unwrap_or_default().to_string_lossy()).strong());
+
+                ui::ScrollArea::vertical().max_height(300.).show(ui, |ui|
+                {
+                    for image in images
+                    {
+                        if ui.selectable_label(false, ui::RichText::new(image).monospace()).clicked()
+                        {
+                            picked = Some(image.clone())
+                        }
+                    }
+                });
+            });
+
+        if let Some(image) = picked
+        {
+            match download::extract_sheet(pack, &image)
+            {
+                Ok(path) => self.open_spritesheet(path),
+                Err(err) => self.toast = Some((format!("couldn't read {image}: {err}"), std::time::Instant::now()))
+            }
+
+            open = false
+        }
+
+        if !open
+        {
+            self.asset_pack = None
+        }
+    }
+
+    fn sheet_error_window(&mut self, app: &mut app::App)
+    {
+        let workspace = &mut self.workspaces[self.active_workspace];
+
+        let Some(ref error) = workspace.sheet_error else
+        {
+            return
+        };
+
+        let (mut relocate, mut dismiss) = (false, false);
+
+        ui::Window::new("spritesheet not found")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(ui::Align2::CENTER_TOP, (0., 40.))
+            .show(app.ui().context(), |ui|
+            {
+                ui.label(error);
+                ui.label(ui::RichText::new("the tiles are still there, pick the image again to see them").weak());
+
+                ui.horizontal(|ui|
+                {
+                    relocate = ui.button("relocate").clicked();
+                    dismiss = ui.button("dismiss").clicked();
+                });
+            });
+
+        if relocate
+        {
+            if let Some(path) = file_dialog(self.sheet_folder.as_deref())
+                .add_filter("", &["png"])
+                .set_file_name("choose the moved spritesheet")
+                .pick_file()
+            {
+                self.sheet_folder = path.parent().map(PathBuf::from);
+                workspace.relocate_sheet(path)
+            }
+        }
+
+        if dismiss
+        {
+            workspace.sheet_error = None
+        }
+    }
+
+    fn missing_tiles_window(&mut self, app: &mut app::App)
+    {
+        let workspace = &mut self.workspaces[self.active_workspace];
+
+        let Some(&SpriteSheet { rows, columns, .. }) = workspace.sprite_sheet.as_ref().filter(|_| !workspace.missing_tiles.is_empty()) else
+        {
+            return
+        };
+
+        let (mut clear, mut clamp, mut keep) = (false, false, false);
+
+        ui::Window::new("tiles missing from the new sheet")
+            .collapsible(false)
+            .anchor(ui::Align2::CENTER_TOP, (0., 40.))
+            .show(app.ui().context(), |ui|
+            {
+                ui.label(format!
+                (
+                    "the sheet only has {} tiles, {} placed tiles use an index past them",
+                    rows * columns,
+                    workspace.missing_tiles.len()
+                ));
+
+                ui::ScrollArea::vertical().max_height(240.).show(ui, |ui|
+                {
+                    for (pos, idx) in &workspace.missing_tiles
+                    {
+                        ui.horizontal(|ui|
+                        {
+                            let shown = workspace.settings.shown(*pos);
+                            ui.label(ui::RichText::new(format!("{}, {}  tile {idx}", shown.x, shown.y)).monospace());
+
+                            if ui.small_button("go to").clicked()
+                            {
+                                self.jump_to = Some(*pos)
+                            }
+                        });
+                    }
+                });
+
+                ui.horizontal(|ui|
+                {
+                    clear = ui.button("clear").on_hover_text("erase them").clicked();
+                    clamp = ui.button("clamp").on_hover_text("point them at the last tile of the sheet").clicked();
+                    keep = ui.button("keep").on_hover_text("leave them showing whatever their uv points at").clicked();
+                });
+            });
+
+        if clear || clamp
+        {
+            let cells = workspace.missing_tiles.iter().map(|(pos, _)| *pos);
+
+            let uv = if clamp
+            {
+                tile_uv((rows * columns).saturating_sub(1), rows, columns)
+            }
+            else
+            {
+                ui::Rect::NOTHING
+            };
+
+            let operation = oplog::Operation::stroke(cells.clone(), uv);
+            workspace.edit(cells.map(|pos| (pos, uv)).collect(), operation)
+        }
+
+        if clear || clamp || keep
+        {
+            workspace.missing_tiles.clear()
+        }
+    }
+
+    fn note_window(&mut self, app: &mut app::App)
+    {
+        let Some((pos, ref mut entry)) = self.note_entry else
+        {
+            return
+        };
+
+        let workspace = &mut self.workspaces[self.active_workspace];
+
+        let mut done = false;
+
+        let shown = workspace.settings.shown(pos);
+
+        ui::Window::new(format!("note at {}, {}", shown.x, shown.y))
+            .id(ui::Id::new("cell note"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(ui::Align2::CENTER_TOP, (0., 40.))
+            .show(app.ui().context(), |ui|
+            {
+                ui.text_edit_singleline(entry).request_focus();
+                ui.label(ui::RichText::new("enter to save, an empty note removes it").weak());
+
+                if ui.input(|input| input.key_pressed(ui::Key::Enter))
+                {
+                    match entry.trim()
+                    {
+                        "" => workspace.notes.remove(&pos),
+                        note => workspace.notes.insert(pos, note.to_owned())
+                    };
+
+                    workspace.dirty = true;
+
+                    done = true
+                }
+
+                if ui.input(|input| input.key_pressed(ui::Key::Escape))
+                {
+                    done = true
+                }
+            });
+
+        if done
+        {
+            self.note_entry = None
+        }
+    }
+
+    fn object_window(&mut self, app: &mut app::App)
+    {
+        let workspace = &mut self.workspaces[self.active_workspace];
+
+        let Some(idx) = workspace.selected_object.filter(|idx| *idx < workspace.objects.len()) else
+        {
+            return
+        };
+
+        let object = &mut workspace.objects[idx];
+        let before = object.clone();
+
+        let (mut close, mut delete) = (false, false);
+
+        ui::Window::new("object")
+            .collapsible(false)
+            .resizable(false)
+            .show(app.ui().context(), |ui|
+            {
+                ui::Grid::new("object fields").num_columns(2).show(ui, |ui|
+                {
+                    ui.label("kind");
+
+                    // new objects take the kind last typed, so placing a few of the same is quick
+                    if ui.text_edit_singleline(&mut object.kind).changed()
+                    {
+                        self.object_kind = object.kind.clone()
+                    }
+
+                    ui.end_row();
+
+                    ui.label("position");
+                    ui.horizontal(|ui|
+                    {
+                        ui.add(ui::DragValue::new(&mut object.x).speed(0.05));
+                        ui.add(ui::DragValue::new(&mut object.y).speed(0.05));
+                    });
+                    ui.end_row();
+
+                    if let Some((ref mut width, ref mut height)) = object.size
+                    {
+                        ui.label("size");
+                        ui.horizontal(|ui|
+                        {
+                            ui.add(ui::DragValue::new(width).speed(0.05).clamp_range(0. ..=f64::MAX));
+                            ui.add(ui::DragValue::new(height).speed(0.05).clamp_range(0. ..=f64::MAX));
+                        });
+                        ui.end_row();
+                    }
+                });
+
+                ui.separator();
+
+                let mut removed = None;
+
+                ui::Grid::new("object properties").num_columns(3).show(ui, |ui|
+                {
+                    for (property, (name, value)) in object.properties.iter_mut().enumerate()
+                    {
+                        ui.add(ui::TextEdit::singleline(name).hint_text("name").desired_width(90.));
+                        ui.add(ui::TextEdit::singleline(value).hint_text("value").desired_width(120.));
+
+                        if ui.small_button("🗑").clicked()
+                        {
+                            removed = Some(property)
+                        }
+
+                        ui.end_row();
+                    }
+                });
+
+                if let Some(property) = removed
+                {
+                    object.properties.remove(property);
+                }
+
+                if ui.button("add property").clicked()
+                {
+                    object.properties.push((String::new(), String::new()))
+                }
+
+                ui.separator();
+
+                ui.horizontal(|ui|
+                {
+                    delete = ui.button("delete").clicked();
+                    close = ui.button("close").clicked();
+                });
+            });
+
+        workspace.dirty |= *object != before || delete;
+
+        if delete
+        {
+            workspace.objects.remove(idx);
+        }
+
+        if close || delete
+        {
+            workspace.selected_object = None
+        }
+    }
+
+    /// the right click menu of an object, moving it above or below the others
+    fn object_menu(&mut self, app: &mut app::App)
+    {
+        let Some((idx, screen_pos)) = self.object_menu else
+        {
+            return
+        };
+
+        let workspace = &mut self.workspaces[self.active_workspace];
+
+        if idx >= workspace.objects.len()
+        {
+            self.object_menu = None;
+            return
+        }
+
+        let response = ui::Area::new(ui::Id::new("object menu"))
+            .fixed_pos(screen_pos)
+            .order(ui::Order::Foreground)
+            .show(app.ui().context(), |ui|
+            {
+                ui::Frame::popup(ui.style()).show(ui, |ui|
+                {
+                    if ui.button("bring to front").clicked()
+                    {
+                        workspace.reorder_object(idx, true);
+                        self.object_menu = None
+                    }
+
+                    if ui.button("send to back").clicked()
+                    {
+                        workspace.reorder_object(idx, false);
+                        self.object_menu = None
+                    }
+                });
+            })
+            .response;
+
+        if response.clicked_elsewhere()
+        {
+            self.object_menu = None
+        }
+    }
+
+    fn cell_menu(&mut self, app: &mut app::App)
+    {
+        let Some((pos, screen_pos)) = self.cell_menu else
+        {
+            return
+        };
+
+        let workspace = &mut self.workspaces[self.active_workspace];
+
+        if !workspace.tiles.contains_key(&pos)
+        {
+            self.cell_menu = None;
+            return
+        }
+
+        let response = ui::Area::new(ui::Id::new("cell menu"))
+            .fixed_pos(screen_pos)
+            .order(ui::Order::Foreground)
+            .show(app.ui().context(), |ui|
+            {
+                ui::Frame::popup(ui.style()).show(ui, |ui|
+                {
+                    if ui.button("select all matching")
+                        .on_hover_text("selects every cell holding this tile, to move, delete, replace or export them together")
+                        .clicked()
+                    {
+                        workspace.select_matching(pos);
+                        self.cell_menu = None
+                    }
+
+                    if ui.button("note").clicked()
+                    {
+                        self.note_entry = Some((pos, workspace.notes.get(&pos).cloned().unwrap_or_default()));
+                        self.cell_menu = None
+                    }
+                });
+            })
+            .response;
+
+        if response.clicked_elsewhere()
+        {
+            self.cell_menu = None
+        }
+    }
+
+    fn stray_tiles_window(&mut self, app: &mut app::App)
+    {
+        if !self.show_stray_tiles
+        {
+            return
+        }
+
+        let workspace = &mut self.workspaces[self.active_workspace];
+        let strays = workspace.stray_tiles();
+        let settings = &workspace.settings;
+
+        let mut erase = false;
+
+        ui::Window::new("stray tiles")
+            .open(&mut self.show_stray_tiles)
+            .collapsible(false)
+            .resizable(false)
+            .show(app.ui().context(), |ui|
+            {
+                if strays.is_empty()
+                {
+                    ui.label(ui::RichText::new("every tile is close to the rest").weak());
+                    return
+                }
+
+                ui.label(format!("{} tiles far from the rest or past the coordinate limit", strays.len()));
+
+                ui::ScrollArea::vertical().max_height(240.).show(ui, |ui|
+                {
+                    for pos in &strays
+                    {
+                        ui.horizontal(|ui|
+                        {
+                            let shown = settings.shown(*pos);
+                            ui.label(ui::RichText::new(format!("{}, {}", shown.x, shown.y)).monospace());
+
+                            if ui.small_button("go to").clicked()
+                            {
+                                self.jump_to = Some(*pos)
+                            }
+                        });
+                    }
+                });
+
+                erase = ui.button("erase them all").clicked();
+            });
+
+        if erase
+        {
+            let operation = oplog::Operation::stroke(strays.iter().copied(), ui::Rect::NOTHING);
+            workspace.edit(strays.into_iter().map(|pos| (pos, ui::Rect::NOTHING)).collect(), operation)
+        }
+    }
+
+    /// tells in the toast why `what` couldn't be done, if it couldn't
+    fn report(&mut self, what: &str, result: Result<(), impl std::fmt::Display>)
+    {
+        if let Err(err) = result
+        {
+            self.toast = Some((format!("couldn't {what}: {err}"), std::time::Instant::now()))
+        }
+    }
+
+    fn toast(&mut self, app: &mut app::App)
+    {
+        let Some((ref message, since)) = self.toast else
+        {
+            return
+        };
+
+        let Some(remaining) = TOAST_DURATION.checked_sub(since.elapsed()) else
+        {
+            self.toast = None;
+            return
+        };
+
+        let ctx = app.ui().context();
+
+        ui::Area::new(ui::Id::new("toast"))
+            .anchor(ui::Align2::RIGHT_BOTTOM, (-12., -12.))
+            .interactable(false)
+            .show(ctx, |ui|
+            {
+                ui::Frame::popup(ui.style()).show(ui, |ui| ui.label(message));
+            });
+
+        ctx.request_repaint_after(remaining)
+    }
+
+    /// shows how far the folder conversion got, then what it did to each workspace
+    fn batch_results_window(&mut self, app: &mut app::App)
+    {
+        if let Some(ref folder_conversion) = self.folder_conversion
+        {
+            match folder_conversion.poll()
+            {
+                Some(results) =>
+                {
+                    self.folder_conversion = None;
+                    self.batch_results = Some(results)
+                }
+                None =>
+                {
+                    let (done, total) = folder_conversion.progress();
+
+                    ui::Window::new("converting folder")
+                        .collapsible(false)
+                        .resizable(false)
+                        .show(app.ui().context(), |ui|
+                        {
+                            ui.label(format!("{done} of {total} workspaces"));
+                            ui.add(ui::ProgressBar::new(done as f32 / total.max(1) as f32).show_percentage())
+                        });
+
+                    app.ui().context().request_repaint()
+                }
+            }
+        }
+
+        let Some(ref results) = self.batch_results else
+        {
+            return
+        };
+
+        let mut open = true;
+
+        ui::Window::new("folder conversion")
+            .open(&mut open)
+            .collapsible(false)
+            .show(app.ui().context(), |ui|
+            {
+                let failed = results.iter().filter(|(_, result)| result.is_err()).count();
+
+                ui.label(format!("{} converted, {failed} failed", results.len() - failed));
+
+                ui::ScrollArea::vertical().max_height(300.).show(ui, |ui|
+                {
+                    for (path, result) in results
+                    {
+                        let name = path.file_name().map_or(String::new(), |name| name.to_string_lossy().into_owned());
+
+                        match result
+                        {
+                            Ok(_) => ui.label(ui::RichText::new(name).monospace()),
+                            Err(err) => ui.label(ui::RichText::new(format!("{name}: {err}")).monospace().color(ui.visuals().error_fg_color))
+                        };
+                    }
+                });
+            });
+
+        if !open
+        {
+            self.batch_results = None
+        }
+    }
+
+    fn project_browser_window(&mut self, app: &mut app::App)
+    {
+        let Some(ref mut project_browser) = self.project_browser else
+        {
+            return
+        };
+
+        // a thumbnail drawn again keeps its path, the old image would stay otherwise
+        for thumbnail in project_browser.poll()
+        {
+            app.ui().context().forget_image(&sheet_uri(&thumbnail))
+        }
+
+        if project_browser.drawing()
+        {
+            app.ui().context().request_repaint_after(std::time::Duration::from_millis(100))
+        }
+
+        let project_browser = &*project_browser;
+
+        let mut open = true;
+        let (mut opened, mut rescan) = (None, false);
+
+        ui::Window::new(format!("projects in {}", project_browser.folder.display()))
+            .id(ui::Id::new("project browser"))
+            .open(&mut open)
+            .collapsible(false)
+            .show(app.ui().context(), |ui|
+            {
+                if project_browser.entries.is_empty()
+                {
+                    ui.label(ui::RichText::new("no saved workspaces in this folder").weak());
+                }
+
+                ui::ScrollArea::vertical().max_height(400.).show(ui, |ui|
+                {
+                    for entry in &project_browser.entries
+                    {
+                        let row = ui.horizontal(|ui|
+                        {
+                            let (rect, _) = ui.allocate_exact_size(ui::vec2(96., 96.), ui::Sense::hover());
+
+                            if let Some(ref thumbnail) = entry.thumbnail
+                            {
+                                ui.put(rect, ui::Image::from_uri(sheet_uri(thumbnail)).shrink_to_fit());
+                            }
+                            else if entry.drawing
+                            {
+                                ui.put(rect, ui::Spinner::new());
+                            }
+
+                            ui.vertical(|ui|
+                            {
+                                ui.label(ui::RichText::new(&entry.name).strong());
+
+                                if let Some(modified) = entry.modified
+                                {
+                                    ui.label(ui::RichText::new(browser::format_age(modified)).weak());
+                                }
+                            });
+                        });
+
+                        // the whole row can be double clicked, not only the name
+                        let row = ui.interact(row.response.rect, ui::Id::new(&entry.path), ui::Sense::click());
+
+                        if row.hovered()
+                        {
+                            ui.painter().rect_filled(row.rect, 2., ui::Color32::from_white_alpha(8));
+                        }
+
+                        if row.on_hover_text("double click to open").double_clicked()
+                        {
+                            opened = Some(entry.path.clone())
+                        }
+                    }
+                });
+
+                ui.separator();
+
+                rescan = ui.button("rescan").clicked();
+            });
+
+        if rescan
+        {
+            // the thumbnails may have been redrawn under the same path
+            for thumbnail in project_browser.entries.iter().filter_map(|entry| entry.thumbnail.as_ref())
+            {
+                app.ui().context().forget_image(&sheet_uri(thumbnail))
+            }
+
+            self.project_browser = Some(browser::ProjectBrowser::scan(project_browser.folder.clone()))
+        }
+
+        if let Some(path) = opened
+        {
+            self.open_saved(path);
+            open = false
+        }
+
+        if !open
+        {
+            self.project_browser = None
+        }
+    }
+
+    /// shows the whole spritesheet so a single tile can be dragged over it,
+    /// then sizes the grid so that tile fits exactly
+    fn grid_fit_window(&mut self, app: &mut app::App)
+    {
+        let workspace = &mut self.workspaces[self.active_workspace];
+
+        let (Some(SpriteSheet { path, rows, columns }), Some((width, height))) =
+            (&mut workspace.sprite_sheet, workspace.sheet_dimensions)
+        else
+        {
+            return
+        };
+
+        let sample = &mut self.grid_sample;
+        let mut applied = false;
+
+        ui::Window::new("fit grid to a tile")
+            .open(&mut self.show_grid_fit)
+            .resizable(false)
+            .show(app.ui().context(), |ui|
+            {
+                ui.label(ui::RichText::new("drag over a single tile of the sheet").weak());
+
+                // big sheets are shrunk to fit, the sample is kept in sheet pixels
+                let scale = (512. / width.max(height) as f32).min(4.);
+                let sheet_size = ui::vec2(width as f32, height as f32);
+
+                let response = ui.add
+                (
+                    ui::Image::from_uri(sheet_uri(path))
+                        .fit_to_exact_size(sheet_size * scale)
+                        .sense(ui::Sense::drag())
+                );
+
+                if let Some(pointer) = response.interact_pointer_pos()
+                {
+                    let pixel = ((pointer - response.rect.min) / scale)
+                        .to_pos2()
+                        .clamp(ui::Pos2::ZERO, sheet_size.to_pos2());
+
+                    match sample
+                    {
+                        Some((_, end)) if !response.drag_started() => *end = pixel,
+                        _ => *sample = Some((pixel, pixel))
+                    }
+                }
+
+                let Some((start, end)) = *sample else
+                {
+                    return
+                };
+
+                let tile = ui::Rect::from_two_pos(start, end);
+
+                ui.painter().rect_stroke
+                (
+                    ui::Rect::from_min_size(response.rect.min + tile.min.to_vec2() * scale, tile.size() * scale),
+                    0.,
+                    ui::Stroke::new(1.5, ui::Color32::from_rgb(230, 150, 60))
+                );
+
+                // a sample thinner than a pixel can't tell anything
+                if tile.width() < 1. || tile.height() < 1.
+                {
+                    return
+                }
+
+                // the sample is only approximate, so the grid snaps to even divisions
+                let fit_rows = nearest_divisor(width as usize, (width as f32 / tile.width()).round() as usize);
+                let fit_columns = nearest_divisor(height as usize, (height as f32 / tile.height()).round() as usize);
+
+                ui.horizontal(|ui|
+                {
+                    ui.label(ui::RichText::new(format!("{fit_rows} rows, {fit_columns} columns")).monospace());
+
+                    if ui.button("apply").clicked()
+                    {
+                        *rows = fit_rows;
+                        *columns = fit_columns;
+
+                        applied = true
+                    }
+                });
+            });
+
+        if applied
+        {
+            self.show_grid_fit = false;
+            self.grid_sample = None
+        }
+    }
+
+    /// lets the format and the empty cell value be chosen before exporting the index grid
+    fn grid_export_window(&mut self, app: &mut app::App)
+    {
+        let workspace = &self.workspaces[self.active_workspace];
+        let options = &mut self.grid_export;
+
+        let mut exported = None;
+
+        ui::Window::new("export index grid")
+            .open(&mut self.show_grid_export)
+            .resizable(false)
+            .show(app.ui().context(), |ui|
+            {
+                ui.horizontal(|ui|
+                {
+                    ui.radio_value(&mut options.format, export::GridFormat::Csv, "csv");
+                    ui.radio_value(&mut options.format, export::GridFormat::Json, "json");
+                });
+
+                ui.horizontal(|ui|
+                {
+                    ui.label("empty cells are");
+
+                    ui.add(ui::TextEdit::singleline(&mut options.empty_cell).desired_width(60.))
+                        .on_hover_text("like -1, 0 or null, anything else is written as text");
+                });
+
+                ui.add_enabled
+                (
+                    workspace.selection_bounds().is_some(),
+                    ui::Checkbox::new(&mut options.selection_only, "only the selection")
+                )
+                .on_hover_text("the json also records where the selection sits in the map");
+
+                if ui.button("export").clicked()
+                {
+                    exported = Some(workspace.export_grid(options))
+                }
+            });
+
+        if let Some(exported) = exported
+        {
+            self.show_grid_export = false;
+            self.report("export the index grid", exported)
+        }
+    }
+
+    /// lets the solid tiles be picked and the padding and erosion be chosen before exporting the walkable cells
+    fn walk_export_window(&mut self, app: &mut app::App)
+    {
+        let workspace = &mut self.workspaces[self.active_workspace];
+        let options = &mut self.walk_export;
+
+        let Some(SpriteSheet { ref path, rows, columns }) = workspace.sprite_sheet else
+        {
+            return
+        };
+
+        let mut exported = None;
+
+        ui::Window::new("export walkable grid")
+            .open(&mut self.show_walk_export)
+            .resizable(false)
+            .show(app.ui().context(), |ui|
+            {
+                ui.label(ui::RichText::new("click the tiles agents can't walk over").weak());
+
+                let texture_options = workspace.settings.texture_options();
+                let source = sheet_source(path, &workspace.fallback_texture);
+                let solid_tiles = &mut workspace.settings.solid_tiles;
+
+                ui::ScrollArea::vertical().max_height(200.).show(ui, |ui| ui.horizontal_wrapped(|ui|
+                {
+                    ui.spacing_mut().item_spacing = (2., 2.).into();
+
+                    for (idx, image) in load_images(source, rows, columns, 0..rows * columns, texture_options)
+                    {
+                        let solid = solid_tiles.contains(&idx);
+                        let tile = ui.add(ui::ImageButton::new(image.fit_to_exact_size(ui::vec2(24., 24.))).selected(solid));
+
+                        if solid
+                        {
+                            ui.painter().rect_filled(tile.rect, 0., ui::Color32::from_rgba_unmultiplied(235, 90, 90, 90));
+                        }
+
+                        if tile.on_hover_text(format!("tile {idx}")).clicked()
+                        {
+                            if solid
+                            {
+                                solid_tiles.remove(&idx);
+                            }
+                            else
+                            {
+                                solid_tiles.insert(idx);
+                            }
+                        }
+                    }
+                }));
+
+                ui.separator();
+
+                ui.checkbox(&mut options.empty_walkable, "empty cells are walkable");
+
+                ui.horizontal(|ui|
+                {
+                    ui.label("erosion");
+                    ui.add(ui::DragValue::new(&mut options.erosion).clamp_range(0..=8).suffix(" cells"))
+                        .on_hover_text("keeps agents this far from the solid cells, so they don't clip the walls");
+                });
+
+                ui.horizontal(|ui|
+                {
+                    ui.label("padding");
+                    ui.add(ui::DragValue::new(&mut options.padding).clamp_range(0..=8).suffix(" cells"))
+                        .on_hover_text("solid cells added around the map");
+                });
+
+                ui.horizontal(|ui|
+                {
+                    ui.radio_value(&mut options.format, export::WalkFormat::RunLength, "run length");
+                    ui.radio_value(&mut options.format, export::WalkFormat::Bitset, "bitset");
+                });
+
+                if ui.button("export").clicked()
+                {
+                    exported = Some(workspace.export_walkable(options))
+                }
+            });
+
+        if let Some(exported) = exported
+        {
+            self.show_walk_export = false;
+            self.report("export the walkable grid", exported)
+        }
+    }
+
+    fn stats_window(&mut self, app: &mut app::App)
+    {
+        let workspace = &self.workspaces[self.active_workspace];
+
+        ui::Window::new("stats")
+            .open(&mut self.show_stats)
+            .resizable(false)
+            .show(app.ui().context(), |ui|
+            {
+                ui::Grid::new("stats grid").num_columns(2).show(ui, |ui|
+                {
+                    ui.label("tiles");
+                    ui.label(ui::RichText::new(workspace.tiles.len().to_string()).monospace());
+                    ui.end_row();
+
+                    // blank tiles still occupy their cell, they are just drawn as nothing
+                    let blank_count = match (&workspace.sprite_sheet, &workspace.blank_tiles)
+                    {
+                        (Some(sprite_sheet), Some((_, blank))) => workspace.tiles
+                            .values()
+                            .filter_map(|uv| tile_index(*uv, sprite_sheet.rows, sprite_sheet.columns))
+                            .filter(|idx| blank.contains(idx))
+                            .count(),
+                        _ => 0
+                    };
+
+                    ui.label("of which blank");
+                    ui.label(ui::RichText::new(blank_count.to_string()).monospace());
+                    ui.end_row();
+
+                    ui.label("save file size");
+                    ui.label(ui::RichText::new(format_size(workspace.estimated_save_size())).monospace());
+                    ui.end_row();
+
+                    // the extent as the axes and the exports number the cells
+                    let extent = export::bounds(&workspace.tiles).map(|(min, max)|
+                    (
+                        workspace.settings.shown(min),
+                        workspace.settings.shown(max),
+                        // worked out wide, the cells can span the whole range of an i32
+                        max.x.abs_diff(min.x) as u64 + 1,
+                        max.y.abs_diff(min.y) as u64 + 1
+                    ));
+
+                    ui.label("bounds");
+                    ui.label(ui::RichText::new(match extent
+                    {
+                        Some((min, max, ..)) => format!("x {} to {}, y {} to {}", min.x, max.x, min.y, max.y),
+                        None => "empty".to_owned()
+                    })
+                    .monospace());
+                    ui.end_row();
+
+                    ui.label("size");
+                    ui.label(ui::RichText::new(match extent
+                    {
+                        Some((.., width, height)) => format!("{width} x {height} cells"),
+                        None => "empty".to_owned()
+                    })
+                    .monospace());
+                    ui.end_row();
+                });
+
+                let Some(sprite_sheet) = workspace.sprite_sheet.as_ref().filter(|_| !workspace.tiles.is_empty()) else
+                {
+                    return
+                };
+
+                // the tiles placed the most, the ones a map is mostly made of
+                let mut usage = HashMap::<usize, usize>::new();
+
+                for idx in workspace.tiles.values().filter_map(|uv| tile_index(*uv, sprite_sheet.rows, sprite_sheet.columns))
+                {
+                    *usage.entry(idx).or_default() += 1
+                }
+
+                let mut usage = usage.into_iter().collect::<Vec<_>>();
+                usage.sort_by_key(|(idx, count)| (std::cmp::Reverse(*count), *idx));
+
+                ui.separator();
+                ui.label(format!("{} different tiles, the most used:", usage.len()));
+
+                ui::Grid::new("usage grid").num_columns(2).show(ui, |ui|
+                {
+                    for (idx, count) in usage.into_iter().take(STATS_USAGE_ROWS)
+                    {
+                        ui.label(ui::RichText::new(format!("tile {idx}")).monospace());
+                        ui.label(ui::RichText::new(count.to_string()).monospace());
+                        ui.end_row();
+                    }
+                });
+            });
+    }
+
+    fn bottom_panel(&mut self, app: &mut app::App)
+    {
+        // remapping edits the whole workspace, so it waits for the panel to let go of it
+        let mut remap_from = None;
+        let mut shuffle = false;
+        let mut key_changed = false;
+        let mut reload_sheet = false;
+
+        ui::TopBottomPanel::bottom("assets")
+        .frame(ui::Frame
+        {
+            inner_margin: ui::Margin::symmetric(1., 5.),
+            fill: ui::Color32::from_gray(35),
+            ..Default::default()
+        })
+        .show(app.ui().context(), |ui|
+        {
+            let workspace = &mut self.workspaces[self.active_workspace];
+
+            let Some(SpriteSheet { ref path, ref mut rows, ref mut columns }) = workspace.sprite_sheet else
+            {
+                return
+            };
+    
+            ui.horizontal(|ui|
+            {
+                ui.label(path.to_string_lossy());
+
+                let selected = match workspace.selected_tile
+                {
+                    Some((idx, ..)) => format!("selected tile: {idx}"),
+                    None => "no tile selected".to_owned()
+                };
+
+                ui.label(ui::RichText::new(selected).monospace().weak());
+            });
+
+            // reading the header is enough to know the size
+            if workspace.sheet_dimensions.is_none() && !workspace.sheet_unreadable
+            {
+                match image::image_dimensions(path)
+                {
+                    Ok(dimensions) => workspace.sheet_dimensions = Some(dimensions),
+                    Err(err) =>
+                    {
+                        workspace.sheet_unreadable = true;
+                        workspace.sheet_error = Some(format!("couldn't read {}: {err}", path.display()))
+                    }
+                }
+            }
+
+            let found = workspace.blank_tiles_search.as_ref().and_then(|(slicing, receiver)| match receiver.try_recv()
+            {
+                Ok(blank) => Some((*slicing, blank)),
+                Err(std::sync::mpsc::TryRecvError::Empty) => None,
+                // the thread couldn't read the sheet, there's nothing to leave blank
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => Some((*slicing, HashSet::new()))
+            });
+
+            match found
+            {
+                Some(found) =>
+                {
+                    workspace.blank_tiles = Some(found);
+                    workspace.blank_tiles_search = None
+                }
+                None if workspace.blank_tiles_search.is_some() =>
+                    ui.ctx().request_repaint_after(std::time::Duration::from_millis(100)),
+                None => ()
+            }
+
+            // finding them needs every pixel, so it's done on a thread and only again when the slicing changes,
+            // while the rows or columns are dragged the search waits for the one running instead of piling up
+            if workspace.blank_tiles_search.is_none()
+                && workspace.blank_tiles.as_ref().is_none_or(|(slicing, _)| *slicing != (*rows, *columns))
+            {
+                let (sender, receiver) = std::sync::mpsc::channel();
+                let (path, slicing, color_key) = (path.clone(), (*rows, *columns), workspace.settings.color_key);
+
+                std::thread::spawn(move ||
+                {
+                    // the receiver is gone if the sheet changed meanwhile, nothing to report to
+                    let _ = sender.send(blank_tiles(&path, slicing.0, slicing.1, color_key));
+                });
+
+                workspace.blank_tiles_search = Some((slicing, receiver))
+            }
+
+            if let Some((width, height)) = workspace.sheet_dimensions
+            {
+                let width_remainder = width as usize % (*rows).max(1);
+                let height_remainder = height as usize % (*columns).max(1);
+
+                if width_remainder != 0 || height_remainder != 0
+                {
+                    ui.horizontal(|ui|
+                    {
+                        let warning = format!
+                        (
+                            "the sheet is {width}x{height} px, {} rows leave {width_remainder} px \
+                            and {} columns leave {height_remainder} px, tiles won't line up with the pixels",
+                            rows, columns
+                        );
+
+                        ui.colored_label(ui::Color32::from_rgb(230, 170, 60), warning);
+
+                        if ui.button("snap to nearest even division").clicked()
+                        {
+                            *rows = nearest_divisor(width as usize, *rows);
+                            *columns = nearest_divisor(height as usize, *columns)
+                        }
+                    });
+                }
+            }
+
+            // placed tiles store uvs, which point at different art once the grid changes
+            match workspace.tile_slicing
+            {
+                Some(slicing) if workspace.tiles.is_empty() || slicing == (*rows, *columns) => (),
+                None => workspace.tile_slicing = Some((*rows, *columns)),
+                Some((old_rows, old_columns)) => 
+                {
+                    ui.horizontal(|ui|
+                    {
+                        let warning = format!
+                        (
+                            "the grid went from {old_rows}x{old_columns} to {rows}x{columns}, \
+                            the {} placed tiles now show different parts of the sheet",
+                            workspace.tiles.len()
+                        );
+
+                        ui.colored_label(ui::Color32::from_rgb(230, 120, 90), warning);
+
+                        if ui.button("remap by index").on_hover_text("keep every tile on its index in the new grid, the undo history is cleared").clicked()
+                        {
+                            remap_from = Some((old_rows, old_columns))
+                        }
+
+                        if ui.button("keep").on_hover_text("leave the tiles pointing where they do now").clicked()
+                        {
+                            workspace.tile_slicing = Some((*rows, *columns))
+                        }
+
+                        if ui.button("cancel").on_hover_text("go back to the previous grid").clicked()
+                        {
+                            (*rows, *columns) = (old_rows, old_columns)
+                        }
+                    });
+                }
+            }
+
+            // the art was edited in another program, the tiles and the selection survive a reload
+            if workspace.sheet_changed
+            {
+                ui.horizontal(|ui|
+                {
+                    ui.colored_label(ui::Color32::from_rgb(120, 170, 255), "the spritesheet changed on disk");
+
+                    reload_sheet = ui.button("reload").on_hover_text("keeps the tiles, the selection and the view").clicked();
+
+                    if ui.button("ignore").on_hover_text("keep showing the old art until the next change").clicked()
+                    {
+                        workspace.sheet_changed = false;
+                        workspace.sheet_modified = file_modified(path)
+                    }
+                });
+            }
+
+            // with nothing placed there's nothing to go out of date
+            if workspace.tiles.is_empty()
+            {
+                workspace.tile_slicing = Some((*rows, *columns))
+            }
+
+            ui.separator();
+            
+            // a new sheet keeps its tiles sized so a row of them spans the panel,
+            // between a handful for tiny sheets and a couple dozen for huge ones,
+            // following the slicing until the scale is picked by hand
+            if workspace.fit_palette
+            {
+                let per_row = (*rows * *columns).clamp(8, 24) as f32;
+                let tile_width = ui.available_width() / per_row - 2.5;
+
+                workspace.settings.asset_preview_scale = (tile_width / 100.).clamp(0.3, 3.)
+            }
+
+            let scale = 100. * workspace.settings.asset_preview_scale;
+            let mut picked = None;
+
+            // huge sheets are split in pages so only a few images are built every frame
+            let tile_count = *rows * *columns;
+            let per_page = match workspace.settings.tiles_per_page
+            {
+                0 => tile_count.max(1),
+                per_page => per_page
+            };
+
+            let page_count = tile_count.div_ceil(per_page).max(1);
+            let settings = &mut workspace.settings;
+
+            // the slicing might have changed since the pages were chosen
+            settings.palette_page = settings.palette_page.min(page_count - 1);
+            settings.second_palette_page = settings.second_palette_page.min(page_count - 1);
+
+            // the order only changes where tiles are shown, they keep their index in the sheet,
+            // and it's dropped once the slicing it was made for changes
+            let order = Some(&settings.palette_order).filter(|order| order.len() == tile_count);
+            let options = settings.texture_options();
+
+            let pages = [settings.palette_page, settings.second_palette_page];
+            let pane_count = if settings.split_palette { 2 } else { 1 };
+
+            for (pane, page) in pages.into_iter().enumerate().take(pane_count)
+            {
+                let first_tile = page * per_page;
+
+                let page_tiles = (first_tile..(first_tile + per_page).min(tile_count))
+                    .map(|position| order.map_or(position, |order| order[position]))
+                    .collect::<Vec<_>>();
+
+                // the images only depend on the slicing and the page, the scale is applied when they are drawn
+                let palette_key = (*rows, *columns, options, page_tiles);
+
+                if workspace.palette_key[pane] != palette_key
+                {
+                    let indices = palette_key.3.iter().copied();
+                    let source = sheet_source(path, &workspace.fallback_texture);
+
+                    workspace.palette_images[pane] = load_images(source, *rows, *columns, indices, options).collect();
+                    workspace.palette_key[pane] = palette_key
+                }
+            }
+
+            let pager = |ui: &mut ui::egui::Ui, page: &mut usize| ui.horizontal(|ui|
+            {
+                if ui.add_enabled(*page > 0, ui::Button::new("<")).clicked()
+                {
+                    *page -= 1
+                }
+
+                ui.label(ui::RichText::new(format!("{} / {page_count}", *page + 1)).monospace());
+
+                if ui.add_enabled(*page + 1 < page_count, ui::Button::new(">")).clicked()
+                {
+                    *page += 1
+                }
+            });
+
+            let collapsable_contents = |ui: &mut ui::egui::Ui|
+            {
+                ui.group(|ui| ui.vertical(|ui|
+                {
+                    let scale_slider = ui.add
+                    (
+                        ui::Slider::new(&mut settings.asset_preview_scale, 0.3..=3.)
+                            .handle_shape(ui::style::HandleShape::Rect
+                            {
+                                aspect_ratio: 0.75
+                            })
+                            .trailing_fill(true)
+                            .show_value(false)
+                    );
+
+                    if scale_slider.changed()
+                    {
+                        workspace.fit_palette = false
+                    }
+
+                        ui.horizontal
+                        (
+                            |ui|
+                            {
+                                ui.label(ui::RichText::new("row").monospace());
+                                ui.add(ui::DragValue::new(rows));
+                            }
+                        );
+                        
+                        ui.horizontal
+                        (
+                            |ui|
+                            {
+                                ui.label(ui::RichText::new("columns").monospace());
+                                ui.add(ui::DragValue::new(columns));
+                            }
+                        );
+
+                        if ui.button("fit to a tile").on_hover_text("drag over one tile of the sheet").clicked()
+                        {
+                            self.show_grid_fit = true
+                        }
+
+                        ui.horizontal(|ui|
+                        {
+                            if ui.button("sort by usage").on_hover_text("the most placed tiles come first").clicked()
+                            {
+                                settings.palette_order = usage_order(&workspace.tiles, *rows, *columns);
+                                settings.palette_page = 0
+                            }
+
+                            if ui.add_enabled(!settings.palette_order.is_empty(), ui::Button::new("reset order")).clicked()
+                            {
+                                settings.palette_order.clear()
+                            }
+                        });
+
+                        let shuffle_button = ui.add_enabled
+                        (
+                            !workspace.variant_tiles.is_empty() && workspace.selected_tile.is_some(),
+                            ui::Button::new("shuffle variants")
+                        );
+
+                        shuffle = shuffle_button
+                            .on_hover_text("swaps the selected tile for a random ctrl clicked variant, in the selection or everywhere")
+                            .clicked();
+
+                        ui.checkbox(&mut settings.smooth_filtering, "smooth filtering")
+                            .on_hover_text("for tilesets that aren't pixel art");
+
+                        ui.horizontal(|ui|
+                        {
+                            let mut keyed = settings.color_key.is_some();
+                            let mut key = settings.color_key.unwrap_or(DEFAULT_COLOR_KEY);
+
+                            let toggled = ui.checkbox(&mut keyed, "color key")
+                                .on_hover_text("for old tilesets drawing their transparent pixels in a single color")
+                                .changed();
+
+                            let picked = ui.add_enabled_ui(keyed, |ui| ui.color_edit_button_srgb(&mut key)).inner.changed();
+
+                            if toggled || picked
+                            {
+                                settings.color_key = Some(key).filter(|_| keyed);
+                                key_changed = true
+                            }
+                        });
+
+                        ui.horizontal(|ui|
+                        {
+                            ui.label(ui::RichText::new("per page").monospace());
+                            ui.add(ui::DragValue::new(&mut settings.tiles_per_page))
+                                .on_hover_text("zero shows every tile at once");
+                        });
+
+                        ui.checkbox(&mut settings.split_palette, "split view")
+                            .on_hover_text("a second pane with its own page, to keep tiles far apart in the sheet in sight");
+
+                        // with the view split each pane has its own pages
+                        if page_count > 1 && !settings.split_palette
+                        {
+                            pager(ui, &mut settings.palette_page);
+                        }
+                }));
+
+                let style = ui.style_mut();
+
+                style.spacing.button_padding = (0.1, 0.1).into();
+                style.spacing.item_spacing = (2.5, 2.5).into();
+
+                style.visuals.widgets.hovered.bg_stroke = ui::Stroke::new(2.5, ui::Color32::LIGHT_GRAY);
+                style.visuals.selection.stroke = ui::Stroke::new(5., ui::Color32::LIGHT_GRAY);
+
+                let mut tile_buttons = |ui: &mut ui::egui::Ui, images: &[(usize, ui::Image<'static>)]|
+                {
+                    for (idx, image) in images
+                    {
+                        let (idx, image) = (*idx, image.clone());
+
+                        let selected = workspace.selected_tile
+                            .is_some_and(|(sel_idx, ..)| idx == sel_idx);
+                    
+                        let uv = image.image_options().uv;
+
+                        let mut tile_display = ui.add_sized
+                        (
+                            (scale,scale),
+                            ui::Button::image(image)
+                                .fill(ui::Color32::TRANSPARENT)
+                                .selected(selected)
+
+                        );
+
+                        // a blank tile would look like a hole in the palette otherwise
+                        if workspace.blank_tiles.as_ref().is_some_and(|(_, blank)| blank.contains(&idx))
+                        {
+                            let rect = tile_display.rect.shrink(4.);
+
+                            ui.painter().add(ui::Shape::dashed_line
+                            (
+                                &[rect.left_top(), rect.right_top(), rect.right_bottom(), rect.left_bottom(), rect.left_top()],
+                                ui::Stroke::new(1., ui::Color32::from_gray(110)),
+                                4.,
+                                3.
+                            ));
+
+                            tile_display = tile_display.on_hover_text("blank tile, it fills the cell without drawing anything");
+                        }
+
+                        if workspace.secondary_tile.is_some_and(|(sec_idx, ..)| idx == sec_idx)
+                        {
+                            ui.painter().rect_stroke
+                            (
+                                tile_display.rect,
+                                0.,
+                                ui::Stroke::new(2.5, ui::Color32::from_rgb(230, 150, 60))
+                            );
+                        }
+
+                        if self.show_palette_indices && tile_display.hovered()
+                        {
+                            let painter = ui.painter();
+                            let galley = painter.layout_no_wrap(idx.to_string(), ui::FontId::monospace(10.), ui::Color32::WHITE);
+                            let rect = ui::Rect::from_min_size(tile_display.rect.left_bottom() - ui::vec2(0., galley.size().y), galley.size())
+                                .expand2(ui::vec2(2., 0.));
+
+                            painter.rect_filled(rect, 2., ui::Color32::from_black_alpha(170));
+                            painter.galley(rect.min + ui::vec2(2., 0.), galley, ui::Color32::WHITE);
+                        }
+
+                        // ctrl clicking gathers the variants a shuffle picks from
+                        if workspace.variant_tiles.contains(&idx)
+                        {
+                            ui.painter().circle_filled(tile_display.rect.right_top() + ui::vec2(-5., 5.), 3.5, ui::Color32::from_rgb(120, 170, 255));
+                        }
+
+                        if tile_display.clicked() && ui.input(|input| input.modifiers.command)
+                        {
+                            match workspace.variant_tiles.iter().position(|variant| *variant == idx)
+                            {
+                                Some(variant) => { workspace.variant_tiles.remove(variant); }
+                                None => workspace.variant_tiles.push(idx)
+                            }
+                        }
+                        else if tile_display.clicked()
+                        {
+                            picked = Some((idx,uv))
+                        }
+
+                        if tile_display.secondary_clicked()
+                        {
+                            workspace.secondary_tile = Some((idx,uv))
+                        }
+                    }
+                };
+
+                if !settings.split_palette
+                {
+                    tile_buttons(ui, &workspace.palette_images[0]);
+                    return
+                }
+
+                // both panes share the selection, only what they show differs
+                let pane_width = (ui.available_width() - ui.spacing().item_spacing.x) / 2.;
+                let pages = [&mut settings.palette_page, &mut settings.second_palette_page];
+
+                for (pane, page) in pages.into_iter().enumerate()
+                {
+                    ui.allocate_ui(ui::vec2(pane_width, PALETTE_PANE_HEIGHT), |ui| ui.vertical(|ui|
+                    {
+                        ui.set_width(pane_width);
+
+                        if page_count > 1
+                        {
+                            pager(ui, page);
+                        }
+
+                        ui::ScrollArea::vertical()
+                            .id_source(("palette pane", pane))
+                            .max_height(PALETTE_PANE_HEIGHT)
+                            .show(ui, |ui| ui.horizontal_wrapped(|ui| tile_buttons(ui, &workspace.palette_images[pane])));
+                    }));
+                }
+            };
+
+            let header_text = ui::RichText::new("tiles")
+                .size(15.)
+                .monospace()
+                .color(ui::Color32::from_gray(100));
+
+            
+
+            ui::CollapsingHeader::new(header_text)
+                .default_open(true)
+                .show(ui, |ui| ui.horizontal_wrapped(collapsable_contents));
+
+            if let Some((idx, uv)) = picked
+            {
+                workspace.select_tile(idx, uv)
+            }
+        });
+
+        if let Some(from) = remap_from
+        {
+            self.workspace_mut().remap_tiles(from)
+        }
+
+        if reload_sheet
+        {
+            self.workspace_mut().reload_sheet(app.ui().context())
+        }
+
+        if key_changed
+        {
+            let workspace = self.workspace_mut();
+
+            // the texture, the palette and the blank tiles all come from the pixels before the key
+            workspace.fallback_texture = None;
+            workspace.blank_tiles = None;
+            workspace.blank_tiles_search = None;
+            workspace.palette_key = [EMPTY_PALETTE_KEY, EMPTY_PALETTE_KEY]
+        }
+
+        if shuffle
+        {
+            self.shuffle_variants()
+        }
+    }
+
+    /// swaps every placed selected tile for a random variant, as a single undoable edit
+    fn shuffle_variants(&mut self)
+    {
+        let workspace = &mut self.workspaces[self.active_workspace];
+        workspace.shuffle_variants(&mut self.rng)
+    }
+
+    fn tool_panel(&mut self, app: &mut app::App)
+    {
+        let mut picked = None;
+
+        ui::SidePanel::left("tools")
+            .resizable(false)
+            .exact_width(36.)
+            .show(app.ui().context(), |ui|
+            {
+                ui.vertical_centered(|ui|
+                {
+                    for tool in Tool::ALL
+                    {
+                        let label = ui::SelectableLabel::new
+                        (
+                            self.current_tool == tool,
+                            ui::RichText::new(tool.icon()).size(18.)
+                        );
+
+                        if ui.add(label).on_hover_text(tool.name()).clicked()
+                        {
+                            picked = Some(tool)
+                        }
+                    }
+                });
+            });
+
+        if let Some(tool) = picked
+        {
+            self.set_tool(tool)
+        }
+    }
+
+    /// notices the spritesheet of the current workspace being changed on disk, looking at it once in a while
+    fn check_sheet_changes(&mut self, app: &mut app::App)
+    {
+        let ctx = app.ui().context();
+
+        // the watchers reload the sheets by themselves, and go away once turned off
+        if self.auto_reload_sheet
+        {
+            self.watch_sheets(ctx);
+            return
+        }
+
+        for workspace in &mut self.workspaces
+        {
+            workspace.sheet_watcher = None
+        }
+
+        // keeps looking even while nothing else asks for a new frame
+        ctx.request_repaint_after(SHEET_CHECK_INTERVAL);
+
+        if self.sheet_checked.elapsed() < SHEET_CHECK_INTERVAL
+        {
+            return
+        }
+
+        self.sheet_checked = std::time::Instant::now();
+
+        let workspace = self.workspace_mut();
+
+        let Some(modified) = workspace.sprite_sheet.as_ref().and_then(|sprite_sheet| file_modified(&sprite_sheet.path)) else
+        {
+            return
+        };
+
+        match workspace.sheet_modified
+        {
+            // the first look only remembers how the file was
+            None => workspace.sheet_modified = Some(modified),
+            Some(read) if modified > read => workspace.sheet_changed = true,
+            Some(_) => ()
+        }
+    }
+
+    /// keeps a watcher on the spritesheet of every workspace and reloads the ones that got saved
+    fn watch_sheets(&mut self, ctx: &ui::Context)
+    {
+        for workspace in &mut self.workspaces
+        {
+            // a sheet that can't be found has no folder worth watching
+            let path = workspace.sprite_sheet
+                .as_ref()
+                .filter(|_| workspace.sheet_error.is_none())
+                .map(|sprite_sheet| &sprite_sheet.path);
+
+            // the watcher follows the sheet through swaps and relocations
+            if workspace.sheet_watcher.as_ref().map(|watcher| &watcher.path) != path
+            {
+                workspace.sheet_watcher = path.and_then(|path| watcher::SheetWatcher::new(path.clone(), ctx.clone()).ok())
+            }
+
+            if workspace.sheet_watcher.as_mut().is_some_and(|watcher| watcher.poll(ctx))
+            {
+                workspace.reload_sheet(ctx)
+            }
+        }
+    }
+
+    /// switches the tool, dropping whatever the previous one was in the middle of
+    fn set_tool(&mut self, tool: Tool)
+    {
+        if tool == self.current_tool
+        {
+            return
+        }
+
+        self.workspace_mut().cancel_drag();
+
+        self.last_painted_cell = None;
+        self.current_tool = tool
+    }
+
+    fn editor_grid(&mut self, app: &mut app::App)
+    {
+        // while the modifier is held the plot neither zooms nor scrolls, the wheel is ours
+        let cycling = app.ui().context().input(|input| self.tile_cycle_modifier.held(input.modifiers));
+        let show_coordinates = self.show_coordinates;
+        let preview = self.preview;
+
+        let workspace = &mut self.workspaces[self.active_workspace];
+
+        let settings::ProjectSettings { background, axis_color, grid_per_tile, origin_offset, .. } = workspace.settings;
+        let texture_options = workspace.settings.texture_options();
+
+        let plot_contents = |ui: &mut plot::PlotUi|
+        {
+            if !preview
+            {
+                ui.vline(plot::VLine::new(0.).color(axis_color));
+                ui.hline(plot::HLine::new(0.).color(axis_color));
+
+                if let Some(Symmetry { horizontal, half_cells }) = self.symmetry
+                {
+                    let color = ui::Color32::from_rgb(190, 120, 255);
+                    let line = half_cells as f64 / 2.;
+
+                    if horizontal
+                    {
+                        ui.hline(plot::HLine::new(line).color(color).style(plot::LineStyle::dashed_dense()))
+                    }
+                    else
+                    {
+                        ui.vline(plot::VLine::new(line).color(color).style(plot::LineStyle::dashed_dense()))
+                    }
+                }
+            }
+
+            // the same texture the palette loaded, nothing can be drawn until it's ready
+            let sheet_texture = workspace.sheet_texture(ui.ctx(), texture_options);
+            workspace.sheet_texture_id = sheet_texture;
+
+            // use the middle click instead of left click
+            if ui.response().dragged_by(ui::PointerButton::Middle)
+            {
+                ui.ctx().set_cursor_icon(ui::CursorIcon::Grabbing);
+                ui.translate_bounds(-ui.pointer_coordinate_drag_delta())
+            }
+
+            // a drag reaching the edge of the canvas pulls the view along,
+            // faster the closer the cursor gets to it or the further past it
+            let drawing = workspace.dragging.is_some()
+                || workspace.shape_drag.is_some()
+                || workspace.selecting
+                || workspace.moving.is_some()
+                || workspace.stamp_capture.is_some()
+                || workspace.object_drag.is_some();
+
+            if let Some(pointer) = ui.response().interact_pointer_pos().filter(|_| drawing)
+            {
+                let rect = ui.response().rect;
+
+                let pull = |distance_to_min: f32, distance_to_max: f32|
+                {
+                    let strength = |distance: f32| (1. - distance / AUTO_SCROLL_MARGIN).clamp(0., 1.);
+                    strength(distance_to_max) - strength(distance_to_min)
+                };
+
+                let pull = ui::vec2
+                (
+                    pull(pointer.x - rect.left(), rect.right() - pointer.x),
+                    // the plot counts up while the screen counts down
+                    pull(rect.bottom() - pointer.y, pointer.y - rect.top())
+                );
+
+                if pull != ui::Vec2::ZERO
+                {
+                    let bounds = ui.plot_bounds();
+                    let cells_per_pixel = bounds.width() as f32 / rect.width();
+                    let step = pull * AUTO_SCROLL_SPEED * ui.ctx().input(|input| input.stable_dt) * cells_per_pixel;
+
+                    ui.translate_bounds(step);
+                    ui.ctx().request_repaint()
+                }
+            }
+
+            // the plot stretches its bounds along with the canvas, which zooms and shifts the map,
+            // so a resized canvas gets the center and the cells per pixel it had before
+            let canvas_size = ui.response().rect.size();
+
+            if canvas_size.x > 0. && canvas_size.y > 0.
+            {
+                let mut bounds = ui.plot_bounds();
+
+                if let Some((last_size, last_bounds)) = self.canvas_view.filter(|(size, _)| *size != canvas_size)
+                {
+                    let center = last_bounds.center();
+                    let cells_per_pixel = last_bounds.width() / last_size.x as f64;
+                    let half_width = canvas_size.x as f64 * cells_per_pixel / 2.;
+                    let half_height = canvas_size.y as f64 * cells_per_pixel / 2.;
+
+                    bounds = plot::PlotBounds::from_min_max
+                    (
+                        [center.x - half_width, center.y - half_height],
+                        [center.x + half_width, center.y + half_height]
+                    );
+
+                    ui.set_plot_bounds(bounds)
+                }
+
+                self.canvas_view = Some((canvas_size, bounds))
+            }
+
+            // a jump keeps the zoom, only moving the view over the cell
+            if let Some(target) = self.jump_to.take()
+            {
+                let bounds = ui.plot_bounds();
+                let (half_width, half_height) = (bounds.width() / 2., bounds.height() / 2.);
+                let (x, y) = (target.x as f64 + 0.5, target.y as f64 + 0.5);
+
+                ui.set_plot_bounds(plot::PlotBounds::from_min_max
+                (
+                    [x - half_width, y - half_height],
+                    [x + half_width, y + half_height]
+                ));
+            }
+
+            if let Some(cell_points) = self.zoom_reset.take()
+            {
+                let center = ui.plot_bounds().center();
+                let size = ui.response().rect.size();
+                let (half_width, half_height) = (size.x as f64 / cell_points / 2., size.y as f64 / cell_points / 2.);
+
+                ui.set_plot_bounds(plot::PlotBounds::from_min_max
+                (
+                    [center.x - half_width, center.y - half_height],
+                    [center.x + half_width, center.y + half_height]
+                ));
+            }
+
+            self.hovered_cell = None;
+
+            if cycling && ui.response().hovered()
+            {
+                // shift turns vertical scrolling horizontal, so both axes count
+                let scroll = ui.ctx().input(|input| input.raw_scroll_delta);
+                let scroll = scroll.x + scroll.y;
+
+                if scroll != 0.
+                {
+                    workspace.cycle_recent_tiles(if scroll > 0. { -1 } else { 1 })
+                }
+            }
+
+            let bounds = ui.plot_bounds();
+
+            self.visible_cells = Some(Selection
+            {
+                start: TilePos { x: bounds.min()[0].floor() as i32, y: bounds.min()[1].floor() as i32 },
+                end: TilePos { x: bounds.max()[0].floor() as i32, y: bounds.max()[1].floor() as i32 }
+            });
+
+            // the preview only shows the tiles and can't be painted on, though it still pans and zooms
+            if preview
+            {
+                if let Some(texture) = sheet_texture
+                {
+                    draw_tiles(&workspace.tiles, &workspace.opacity, texture, ui);
+                }
+
+                return true
+            }
+
+            // the super-grid stands out from the cell grid, but only while it's used
+            // and the blocks aren't so small on screen that the lines would cover everything
+            let super_grid = self.super_grid_size as f64;
+
+            if self.snap_to_super_grid && bounds.width() / super_grid <= MAX_SUPER_GRID_LINES
+            {
+                let color = ui::Color32::from_rgba_unmultiplied(120, 170, 255, 90);
+
+                let first_x = (bounds.min()[0] / super_grid).floor() as i64;
+                let first_y = (bounds.min()[1] / super_grid).floor() as i64;
+
+                for x in first_x..=(bounds.max()[0] / super_grid).ceil() as i64
+                {
+                    ui.vline(plot::VLine::new(x as f64 * super_grid).color(color).width(1.5));
+                }
+
+                for y in first_y..=(bounds.max()[1] / super_grid).ceil() as i64
+                {
+                    ui.hline(plot::HLine::new(y as f64 * super_grid).color(color).width(1.5));
+                }
+            }
+
+            // when a cell is only a few pixels wide a click lands almost anywhere,
+            // so painting is only allowed once zoomed in enough
+            let cell_pixels = ui.response().rect.width() / bounds.width() as f32;
+            let editable = cell_pixels >= self.min_cell_pixels;
+
+            // the cursor tells what a click would do, the panels keep the default one
+            let panning = ui.response().dragged_by(ui::PointerButton::Middle);
+
+            if let Some(screen_pos) = ui.response().hover_pos().filter(|_| !panning)
+            {
+                let modifiers = ui.ctx().input(|input| input.modifiers);
+                let erasing = workspace.stroke_brush.is_some_and(|(.., uv)| uv == ui::Rect::NOTHING)
+                    || (self.current_tool == Tool::Erase && workspace.stroke_brush.is_none());
+
+                let cursor = if !editable
+                {
+                    ui::CursorIcon::NotAllowed
+                }
+                else if workspace.selecting || modifiers.shift || self.current_tool == Tool::Select
+                {
+                    ui::CursorIcon::Cell
+                }
+                else if modifiers.ctrl && self.clipboard.is_some()
+                {
+                    ui::CursorIcon::Copy
+                }
+                else if workspace.moving.is_some()
+                {
+                    ui::CursorIcon::Grabbing
+                }
+                else if self.current_tool == Tool::Move
+                {
+                    ui::CursorIcon::Grab
+                }
+                else if erasing
+                {
+                    ui::CursorIcon::None
+                }
+                else
+                {
+                    ui::CursorIcon::Crosshair
+                };
+
+                ui.ctx().set_cursor_icon(cursor);
+
+                // there are no custom cursor images, so the eraser is drawn in place of the hidden one
+                if cursor == ui::CursorIcon::None
+                {
+                    let layer = ui::LayerId::new(ui::Order::Foreground, ui::Id::new("eraser cursor"));
+                    let painter = ui.ctx().layer_painter(layer);
+
+                    painter.rect
+                    (
+                        ui::Rect::from_center_size(screen_pos, (12., 12.).into()),
+                        2.,
+                        ui::Color32::from_rgb(235, 140, 150),
+                        ui::Stroke::new(1.5, ui::Color32::WHITE)
+                    );
+                }
+            }
+
+            if let Some(screen_pos) = ui.response().hover_pos()
+            {
+                let mut pos = ui.plot_from_screen(screen_pos);
+
+                // objects aren't tied to the grid, so they use the cursor as is
+                let point = [pos.x, pos.y];
+
+                let floor_pos = plot::PlotPoint { x: pos.x.floor(), y: pos.y.floor() };
+
+                pos.x = floor_pos.x.floor() + 0.5;
+                pos.y = floor_pos.y.floor() + 0.5;
+
+                let cell = TilePos
+                {
+                    x: floor_pos.x as i32,
+                    y: floor_pos.y as i32
+                };
+
+                self.hovered_cell = Some(cell);
+
+                // the cell itself, before magnetic placement or the super-grid move the brush off it
+                let hovered = cell;
+    
+                let response = ui.response().clone();
+
+                let tool = self.current_tool;
+
+                // holding shift while dragging selects cells instead of painting them
+                if response.drag_started_by(ui::PointerButton::Primary)
+                    && (tool == Tool::Select || ui.ctx().input(|input| input.modifiers.shift))
+                {
+                    workspace.selection = Some(Selection { start: cell, end: cell });
+                    workspace.mask = None;
+                    workspace.selecting = true
+                }
+                else if response.drag_released_by(ui::PointerButton::Primary)
+                {
+                    workspace.selecting = false
+                }
+
+                if workspace.selecting
+                {
+                    if let Some(ref mut selection) = workspace.selection
+                    {
+                        selection.end = cell
+                    }
+                }
+
+                // alt dragging picks up every placed tile in the rectangle as one brush,
+                // the eyedropper does it without alt
+                if editable
+                    && !workspace.selecting
+                    && response.drag_started_by(ui::PointerButton::Primary)
+                    && (tool == Tool::Pick || ui.ctx().input(|input| input.modifiers.alt))
+                {
+                    workspace.stamp_capture = Some(cell)
+                }
+
+                if let Some(start) = workspace.stamp_capture
+                {
+                    let area = Selection { start, end: cell };
+
+                    if response.drag_released_by(ui::PointerButton::Primary)
+                    {
+                        workspace.stamp_capture = None;
+
+                        let captured = clipboard::ClipboardTiles::copy(&workspace.tiles, &area);
+
+                        if !captured.tiles.is_empty()
+                        {
+                            workspace.stamp = Some(captured);
+                            self.current_tool = Tool::Paint
+                        }
+                    }
+                    else
+                    {
+                        let (min, max) = (area.min(), area.max());
+                        let (left, bottom) = (min.x as f64, min.y as f64);
+                        let (right, top) = (max.x as f64 + 1., max.y as f64 + 1.);
+
+                        ui.polygon
+                        (
+                            plot::Polygon::new(vec![[left, bottom], [right, bottom], [right, top], [left, top]])
+                                .fill_color(ui::Color32::from_rgba_unmultiplied(230, 150, 60, 20))
+                                .stroke(ui::Stroke::new(1.5, ui::Color32::from_rgb(230, 150, 60)))
+                                .style(plot::LineStyle::dashed_dense())
+                        )
+                    }
+                }
+
+                // the move tool grabs the selection from any of its cells
+                if tool == Tool::Move
+                    && editable
+                    && !workspace.selecting
+                    && response.drag_started_by(ui::PointerButton::Primary)
+                    && workspace.in_selection(cell)
+                {
+                    workspace.moving = Some(cell)
+                }
+
+                if let Some(from) = workspace.moving
+                {
+                    let offset = TilePos { x: cell.x - from.x, y: cell.y - from.y };
+
+                    if response.drag_released_by(ui::PointerButton::Primary)
+                    {
+                        workspace.moving = None;
+
+                        if offset != (TilePos { x: 0, y: 0 })
+                        {
+                            workspace.nudge_selection(offset)
+                        }
+                    }
+                    else if let Some(selection) = workspace.selection_bounds()
+                    {
+                        // the selection only moves once it's dropped, until then its landing spot is outlined
+                        let (min, max) = (selection.min(), selection.max());
+
+                        let (left, bottom) = ((min.x + offset.x) as f64, (min.y + offset.y) as f64);
+                        let (right, top) = ((max.x + offset.x) as f64 + 1., (max.y + offset.y) as f64 + 1.);
+
+                        ui.polygon
+                        (
+                            plot::Polygon::new(vec![[left, bottom], [right, bottom], [right, top], [left, top]])
+                                .fill_color(ui::Color32::TRANSPARENT)
+                                .stroke(ui::Stroke::new(1.5, ui::Color32::from_rgb(120, 170, 255)))
+                                .style(plot::LineStyle::dashed_dense())
+                        )
+                    }
+                }
+
+                if tool == Tool::Object && editable && !workspace.selecting
+                {
+                    if response.drag_started_by(ui::PointerButton::Primary)
+                    {
+                        workspace.object_drag = Some(point)
+                    }
+
+                    if let Some(from) = workspace.object_drag
+                    {
+                        if response.drag_released_by(ui::PointerButton::Primary)
+                        {
+                            workspace.object_drag = None;
+                            workspace.objects.push(objects::MapObject::area(self.object_kind.clone(), from, point));
+                            workspace.dirty = true;
+                            workspace.selected_object = Some(workspace.objects.len() - 1)
+                        }
+                        else
+                        {
+                            let (left, bottom) = (from[0].min(point[0]), from[1].min(point[1]));
+                            let (right, top) = (from[0].max(point[0]), from[1].max(point[1]));
+
+                            ui.polygon
+                            (
+                                plot::Polygon::new(vec![[left, bottom], [right, bottom], [right, top], [left, top]])
+                                    .fill_color(ui::Color32::TRANSPARENT)
+                                    .stroke(ui::Stroke::new(1.5, ui::Color32::WHITE))
+                                    .style(plot::LineStyle::dashed_dense())
+                            )
+                        }
+                    }
+
+                    // clicking an object opens it, clicking anywhere else places a point there
+                    if response.clicked_by(ui::PointerButton::Primary)
+                    {
+                        workspace.selected_object = workspace.objects.iter().rposition(|object| object.contains(point));
+
+                        if workspace.selected_object.is_none()
+                        {
+                            workspace.objects.push(objects::MapObject::point(self.object_kind.clone(), point[0], point[1]));
+                            workspace.dirty = true;
+                            workspace.selected_object = Some(workspace.objects.len() - 1)
+                        }
+                    }
+                }
+
+                // a right click that doesn't drag isn't a stroke, so it opens the note of an empty cell,
+                // the menu of a placed tile, or with the object tool the ordering menu of the object under the cursor
+                let clicked_object = workspace.objects
+                    .iter()
+                    .rposition(|object| object.contains(point))
+                    .filter(|_| tool == Tool::Object);
+
+                if editable && response.clicked_by(ui::PointerButton::Secondary)
+                {
+                    match clicked_object
+                    {
+                        Some(idx) => self.object_menu = Some((idx, screen_pos)),
+                        None if workspace.tiles.contains_key(&cell) => self.cell_menu = Some((cell, screen_pos)),
+                        None => self.note_entry = Some((cell, workspace.notes.get(&cell).cloned().unwrap_or_default()))
+                    }
+                }
+
+                if editable && !workspace.selecting && response.clicked_by(ui::PointerButton::Primary)
+                {
+                    match tool
+                    {
+                        Tool::Fill =>
+                        {
+                            let visible = self.visible_cells.expect("the visible cells are set at the start of the frame");
+
+                            // a fill started inside the selection stays in it, so it can't leak through a doorway
+                            let bounds = workspace.selection
+                                .filter(|selection| selection.contains(cell))
+                                .unwrap_or(visible);
+
+                            let group = if self.fill_across_variants
+                            {
+                                workspace.variant_uvs()
+                            }
+                            else
+                            {
+                                Vec::new()
+                            };
+
+                            // the cells already holding the tile are left out of the edit,
+                            // so filling a region with the tile it already holds changes nothing
+                            if let Some((.., uv)) = workspace.selected_tile
+                            {
+                                let cells = flood_cells(&workspace.tiles, cell, bounds, &group);
+
+                                let alpha = (self.paint_opacity * 255.).round() as u8;
+
+                                let operation = oplog::Operation::stroke(cells.iter().copied(), uv);
+                                workspace.place(cells.iter().map(|pos| (*pos, uv)).collect(), Some(alpha), operation)
+                            }
+                        }
+                        Tool::Pick =>
+                        {
+                            let picked = workspace.tiles.get(&cell).copied().zip(workspace.sprite_sheet.as_ref());
+
+                            if let Some((uv, sprite_sheet)) = picked
+                            {
+                                if let Some(idx) = tile_index(uv, sprite_sheet.rows, sprite_sheet.columns)
+                                {
+                                    // the opacity comes along, so the picked tile paints the way it looks
+                                    self.paint_opacity = workspace.opacity_at(cell) as f32 / 255.;
+                                    workspace.select_tile(idx, uv)
+                                }
+                            }
+                        }
+                        _ => ()
+                    }
+                }
+
+                // only painting is pulled, selecting and erasing stay under the cursor
+                let erasing = workspace.stroke_brush.is_some_and(|(.., uv)| uv == ui::Rect::NOTHING);
+
+                let cell = if self.magnetic_placement && !workspace.selecting && !erasing
+                {
+                    magnet_cell(&workspace.tiles, cell)
+                }
+                else
+                {
+                    cell
+                };
+
+                // blocking out snaps to the top left cell of the super-grid block under the cursor
+                let block = Some(super_grid_block(cell, self.super_grid_size))
+                    .filter(|_| self.snap_to_super_grid && !workspace.selecting && workspace.stamp_capture.is_none());
+
+                let cell = block.map_or(cell, |block| block.top_left());
+
+                pos.x = cell.x as f64 + 0.5;
+                pos.y = cell.y as f64 + 0.5;
+
+                if let Some(block) = block.filter(|_| editable && tool.paints())
+                {
+                    let (min, max) = (block.min(), block.max());
+                    let (left, bottom, right, top) = (min.x as f64, min.y as f64, max.x as f64 + 1., max.y as f64 + 1.);
+
+                    ui.polygon
+                    (
+                        plot::Polygon::new(vec![[left, bottom], [right, bottom], [right, top], [left, top]])
+                            .fill_color(ui::Color32::from_rgba_unmultiplied(120, 170, 255, 25))
+                            .stroke(ui::Stroke::new(1.5, ui::Color32::from_rgb(120, 170, 255)))
+                    );
+                }
+
+                if self.show_crosshair
+                {
+                    let color = ui::Color32::from_white_alpha(60);
+
+                    ui.vline(plot::VLine::new(pos.x).color(color).width(1.));
+                    ui.hline(plot::HLine::new(pos.y).color(color).width(1.));
+                }
+                
+                // a captured stamp lands whole where the anchor puts it, once per click or drag
+                let stamp = workspace.stamp
+                    .clone()
+                    .filter(|_| tool == Tool::Paint && editable && !workspace.selecting && workspace.stamp_capture.is_none());
+
+                if let Some(stamp) = stamp.as_ref()
+                {
+                    if response.clicked_by(ui::PointerButton::Primary) || response.drag_started_by(ui::PointerButton::Primary)
+                    {
+                        let offset = self.stamp_anchor.top_left_offset(stamp.width, stamp.height);
+                        workspace.paste(stamp, TilePos { x: cell.x + offset.x, y: cell.y + offset.y })
+                    }
+                }
+
+                // the primary button paints the selected tile,
+                // the secondary one either erases or paints the secondary tile
+                for button in [ui::PointerButton::Primary, ui::PointerButton::Secondary]
+                {
+                    if workspace.selecting || !editable || !response.drag_started_by(button)
+                    {
+                        continue
+                    }
+
+                    // capturing or painting a stamp isn't a stroke
+                    if button == ui::PointerButton::Primary && (workspace.stamp_capture.is_some() || stamp.is_some())
+                    {
+                        continue
+                    }
+
+                    let brush = match button
+                    {
+                        ui::PointerButton::Primary => tool.brush(workspace.selected_tile),
+                        _ => match self.secondary_button
+                        {
+                            SecondaryButton::Erase => Some(ui::Rect::NOTHING),
+                            SecondaryButton::PaintSecondaryTile => workspace.secondary_tile.map(|(.., uv)| uv)
+                        }
+                    };
+
+                    if let Some(uv) = brush.filter(|_| tool.draws_shapes(self.brush_shape))
+                    {
+                        workspace.shape_drag = Some((button, uv, cell))
+                    }
+                    else if let Some(uv) = brush
+                    {
+                        workspace.dragging = Some(indexmap::IndexMap::with_capacity(8));
+                        workspace.stroke_brush = Some((button, uv));
+                        workspace.touched_cells.clear();
+
+                        // a mouse sends no pressure, so a pen used earlier mustn't keep the brush small
+                        self.last_painted_cell = None;
+                        self.pen_pressure = 1.
+                    }
+                }
+
+                // shapes only touch the map once the drag ends, until then they are previewed
+                if let Some((button, shape_uv, start)) = workspace.shape_drag
+                {
+                    let area = Selection { start, end: cell };
+
+                    let shape_cells = |area| tool.shape_cells(self.brush_shape, area, self.hollow_shapes, self.shape_border);
+
+                    if response.drag_released_by(button)
+                    {
+                        workspace.shape_drag = None;
+
+                        let cells = shape_cells(area)
+                            .into_iter()
+                            .filter(|pos| shape_uv == ui::Rect::NOTHING
+                                || self.paint_mode.allows(workspace.tiles.contains_key(pos))
+                            )
+                            .collect::<Vec<_>>();
+
+                        let alpha = (self.paint_opacity * 255.).round() as u8;
+
+                        let operation = oplog::Operation::stroke(cells.iter().copied(), shape_uv);
+                        workspace.place(cells.iter().map(|pos| (*pos, shape_uv)).collect(), Some(alpha), operation)
+                    }
+                    else
+                    {
+                        // the preview shows the exact cells, a smooth outline would hide how they round,
+                        // huge shapes only get their bounds drawn to keep the frame light
+                        let color = ui::Color32::from_rgb(230, 150, 60);
+                        let cells = shape_cells(area);
+
+                        let square = |min: [f64; 2], max: [f64; 2]|
+                            plot::Polygon::new(vec![min, [max[0], min[1]], max, [min[0], max[1]]]);
+
+                        if cells.len() > MAX_SHAPE_PREVIEW_CELLS
+                        {
+                            let (min, max) = (area.min(), area.max());
+
+                            ui.polygon
+                            (
+                                square([min.x as f64, min.y as f64], [max.x as f64 + 1., max.y as f64 + 1.])
+                                    .fill_color(ui::Color32::TRANSPARENT)
+                                    .stroke(ui::Stroke::new(1.5, color))
+                            );
+                        }
+                        else
+                        {
+                            for pos in cells
+                            {
+                                let (x, y) = (pos.x as f64, pos.y as f64);
+
+                                ui.polygon
+                                (
+                                    square([x, y], [x + 1., y + 1.])
+                                        .fill_color(color.gamma_multiply(0.3))
+                                        .stroke(ui::Stroke::NONE)
+                                );
+                            }
+                        }
+                    }
+                }
+
+                if let Some((button, stroke_uv)) = workspace.stroke_brush
+                {
+                    if response.drag_released_by(button)
+                    {
+                        workspace.stroke_brush = None;
+                        self.last_painted_cell = None;
+
+                        // a sparse stroke might not have placed anything at all
+                        if let Some(edited) = workspace.dragging.take().filter(|edited| !edited.is_empty())
+                        {
+                            workspace.operations.push(oplog::Operation::stroke(edited.keys().copied(), stroke_uv));
+
+                            workspace.dirty = true;
+                            workspace.redos.clear();
+                            workspace.undos.add(edited.into_iter().map(|(pos, (uv, alpha))| (pos, uv, alpha)))
+                        }
+                    }
+                }
+
+                if let (Some(ref mut current_edit_tiles), Some((.., stroke_uv))) = (&mut workspace.dragging, workspace.stroke_brush)
+                {
+                    // tablets report how hard the pen presses, the last value holds while it stays still
+                    let pressure = ui.ctx().input(|input| input.events.iter().rev().find_map(|event| match event
+                    {
+                        ui::Event::Touch { force: Some(force), .. } => Some(*force),
+                        _ => None
+                    }));
+
+                    if let Some(pressure) = pressure
+                    {
+                        self.pen_pressure = pressure.clamp(0., 1.)
+                    }
+
+                    let size = 1 + ((self.max_brush_size - 1) as f32 * self.pen_pressure).round() as i32;
+
+                    // the cursor can skip cells when moving fast,
+                    // so we paint the whole line from where it was last frame
+                    let stroke_cells = match self.last_painted_cell
+                    {
+                        Some(last_cell) => line_cells(last_cell, cell),
+                        None => vec![cell]
+                    };
+
+                    self.last_painted_cell = Some(cell);
+
+                    let super_grid = Some(self.super_grid_size).filter(|_| self.snap_to_super_grid);
+
+                    let symmetry = self.symmetry;
+                    let settings = &mut workspace.settings;
+
+                    // the reflection of a cell on the line is the cell itself, touching it twice does nothing,
+                    // and every dab of the brush goes through the overflow policy whole
+                    let stroke_cells = stroke_cells
+                        .into_iter()
+                        .map(|cell| brush_cells(cell, size, super_grid)
+                            .into_iter()
+                            .flat_map(|cell| [Some(cell), symmetry.map(|symmetry| symmetry.reflect(cell))])
+                            .flatten()
+                            .collect::<Vec<_>>())
+                        .filter(|dab| stroke_uv == ui::Rect::NOTHING || settings.admit(dab))
+                        .flatten()
+                        .collect::<Vec<_>>();
+
+                    let alpha = (self.paint_opacity * 255.).round() as u8;
+
+                    for cell in stroke_cells
+                    {
+                        // every cell gets a single roll per stroke, 
+                        // otherwise hovering it long enough would always paint it,
+                        // erasing clears every cell so it doesn't leave speckles behind
+                        if !workspace.touched_cells.insert(cell)
+                            || (stroke_uv != ui::Rect::NOTHING && !self.rng.chance(self.brush_density))
+                        {
+                            continue
+                        }
+
+                        if stroke_uv != ui::Rect::NOTHING
+                            && (!self.paint_mode.allows(workspace.tiles.contains_key(&cell)) || !workspace.settings.in_bounds(cell))
+                        {
+                            continue
+                        }
+
+                        let old_alpha = workspace.opacity_at(cell);
+
+                        // painting a tile over itself with the same opacity changes nothing worth undoing
+                        if workspace.tiles.get(&cell) == Some(&stroke_uv) && old_alpha == alpha
+                        {
+                            continue
+                        }
+
+                        let old_uv = if stroke_uv == ui::Rect::NOTHING
+                        {
+                            workspace.opacity.remove(&cell);
+                            workspace.tiles.swap_remove(&cell)
+                        }
+                        else
+                        {
+                            workspace.set_opacity_at(cell, alpha);
+                            workspace.tiles.insert(cell, stroke_uv)
+                        };
+
+                        // erasing an empty cell doesn't change anything worth undoing
+                        if stroke_uv != ui::Rect::NOTHING || old_uv.is_some()
+                        {
+                            current_edit_tiles.insert(cell, (old_uv.unwrap_or(ui::Rect::NOTHING), old_alpha));
+                        }
+                    }
+                }
+
+                // this means we have no tile selected to draw,
+                // meaning we don't need to preview anything on the tiles
+                if let (Some(stamp), Some(texture)) = (stamp.as_ref(), sheet_texture)
+                {
+                    let offset = self.stamp_anchor.top_left_offset(stamp.width, stamp.height);
+                    let (left, top) = ((cell.x + offset.x) as f64, (cell.y + offset.y) as f64 + 1.);
+
+                    for (tile_offset, uv) in &stamp.tiles
+                    {
+                        let center = plot::PlotPoint::new(left + tile_offset.x as f64 + 0.5, top + tile_offset.y as f64 - 0.5);
+
+                        ui.image(plot::PlotImage::new(texture, center, (1., 1.)).highlight(true).uv(*uv));
+                    }
+
+                    let (right, bottom) = (left + stamp.width as f64, top - stamp.height as f64);
+
+                    ui.polygon
+                    (
+                        plot::Polygon::new(vec![[left, bottom], [right, bottom], [right, top], [left, top]])
+                            .fill_color(ui::Color32::TRANSPARENT)
+                            .stroke(ui::Stroke::new(1., ui::Color32::from_rgb(230, 150, 60)))
+                    );
+                }
+                else if !workspace.selecting && editable
+                {
+                    // shapes start from a single cell, only freehand strokes cover the whole brush
+                    let brush = if tool.draws_shapes(self.brush_shape)
+                    {
+                        vec![cell]
+                    }
+                    else
+                    {
+                        let size = 1 + ((self.max_brush_size - 1) as f32 * self.pen_pressure).round() as i32;
+                        let cells = brush_cells(cell, size, Some(self.super_grid_size).filter(|_| self.snap_to_super_grid));
+
+                        // the stroke lands on both sides of the line, so the preview shows both
+                        match self.symmetry
+                        {
+                            Some(symmetry) => symmetry.mirrored(cells),
+                            None => cells
+                        }
+                    };
+
+                    // a fill goes from the cell under the cursor, not from where the brush snapped to
+                    let fill = ||
+                    {
+                        let visible = self.visible_cells.expect("the visible cells are set at the start of the frame");
+                        let bounds = workspace.selection.filter(|selection| selection.contains(hovered)).unwrap_or(visible);
+
+                        let group = if self.fill_across_variants
+                        {
+                            workspace.variant_uvs()
+                        }
+                        else
+                        {
+                            Vec::new()
+                        };
+
+                        let cells = flood_cells(&workspace.tiles, hovered, bounds, &group);
+
+                        if workspace.selected_tile.is_some_and(|(.., uv)| cells.iter().all(|pos| workspace.tiles.get(pos) == Some(&uv)))
+                        {
+                            // filling with the tile already there changes nothing, so there's nothing to show
+                            Vec::new()
+                        }
+                        else
+                        {
+                            cells
+                        }
+                    };
+
+                    let square = |pos: TilePos|
+                    {
+                        let (x, y) = (pos.x as f64, pos.y as f64);
+                        plot::Polygon::new(vec![[x, y], [x + 1., y], [x + 1., y + 1.], [x, y + 1.]])
+                    };
+
+                    match tool.hover_preview(workspace.selected_tile.map(|(.., uv)| uv), brush, fill)
+                    {
+                        HoverPreview::Nothing => (),
+                        HoverPreview::Tiles(uv, cells) => if let Some(texture) = sheet_texture
+                        {
+                            for pos in cells
+                            {
+                                let center = plot::PlotPoint::new(pos.x as f64 + 0.5, pos.y as f64 + 0.5);
+                                ui.image(plot::PlotImage::new(texture, center, (1., 1.)).highlight(true).uv(uv));
+                            }
+                        }
+                        HoverPreview::Erase(cells) =>
+                        {
+                            let color = ui::Color32::from_rgb(235, 90, 90);
+
+                            for pos in cells
+                            {
+                                ui.polygon(square(pos).fill_color(color.gamma_multiply(0.2)).stroke(ui::Stroke::new(1.5, color)));
+                            }
+                        }
+                        HoverPreview::Region { uv, start, extent } =>
+                        {
+                            let (min, max) = (extent.min(), extent.max());
+                            let (left, bottom, right, top) = (min.x as f64, min.y as f64, max.x as f64 + 1., max.y as f64 + 1.);
+
+                            ui.polygon
+                            (
+                                plot::Polygon::new(vec![[left, bottom], [right, bottom], [right, top], [left, top]])
+                                    .fill_color(ui::Color32::from_rgba_unmultiplied(230, 150, 60, 15))
+                                    .stroke(ui::Stroke::new(1.5, ui::Color32::from_rgb(230, 150, 60)))
+                                    .style(plot::LineStyle::dashed_dense())
+                            );
+
+                            if let Some(texture) = sheet_texture
+                            {
+                                let center = plot::PlotPoint::new(start.x as f64 + 0.5, start.y as f64 + 0.5);
+                                ui.image(plot::PlotImage::new(texture, center, (1., 1.)).highlight(true).uv(uv));
+                            }
+                        }
+                    }
+                }
+
+                // while ctrl is held show where a paste would land and which cell anchors it
+                if let Some(copied) = self.clipboard.as_ref().filter(|_| ui.ctx().input(|input| input.modifiers.ctrl))
+                {
+                    let offset = self.stamp_anchor.top_left_offset(copied.width, copied.height);
+
+                    let (left, top) = ((cell.x + offset.x) as f64, (cell.y + offset.y) as f64 + 1.);
+                    let (right, bottom) = (left + copied.width as f64, top - copied.height as f64);
+
+                    ui.polygon
+                    (
+                        plot::Polygon::new(vec![[left, bottom], [right, bottom], [right, top], [left, top]])
+                            .fill_color(ui::Color32::from_rgba_unmultiplied(255, 255, 255, 15))
+                            .stroke(ui::Stroke::new(1., ui::Color32::from_gray(220)))
+                    );
+
+                    ui.points
+                    (
+                        plot::Points::new(vec![[pos.x, pos.y]])
+                            .radius(4.)
+                            .color(ui::Color32::from_rgb(230, 150, 60))
+                    );
+                }
+
+                // tell which tile is under the cursor and its note, but not while painting over it
+                if workspace.dragging.is_none() && !workspace.selecting
+                {
+                    let tile = workspace.tiles.get(&cell).copied().zip(workspace.sprite_sheet.as_ref());
+                    let note = workspace.notes.get(&cell);
+
+                    if tile.is_some() || note.is_some()
+                    {
+                        ui::show_tooltip_at_pointer(ui.ctx(), ui::Id::new("hovered tile"), |ui|
+                        {
+                            if let Some((uv, sprite_sheet)) = tile
+                            {
+                                ui.add
+                                (
+                                    ui::Image::new(sheet_source(&sprite_sheet.path, &workspace.fallback_texture))
+                                        .texture_options(texture_options)
+                                        .uv(uv)
+                                        .fit_to_exact_size(ui::vec2(48., 48.))
+                                );
+
+                                match tile_index(uv, sprite_sheet.rows, sprite_sheet.columns)
+                                {
+                                    Some(idx) => ui.label(ui::RichText::new(format!("tile {idx}")).monospace()),
+                                    None => ui.label(ui::RichText::new("not in the current slicing").weak())
+                                };
+                            }
+
+                            if let Some(note) = note
+                            {
+                                ui.label(note);
+                            }
+                        });
+                    }
+                }
+            }
+
+            if let Some(texture) = sheet_texture
+            {
+                draw_tiles(&workspace.tiles, &workspace.opacity, texture, ui);
+            }
+            
+            fn draw_tiles (tiles: &Tiles, opacity: &HashMap<TilePos, u8>, texture: ui::TextureId, ui: &mut plot::PlotUi)
+            {
+                for (pos, uv) in tiles
+                {
+                    // the texture is premultiplied, so the whole tint fades along with its alpha
+                    let tint = match opacity.get(pos)
+                    {
+                        Some(alpha) => ui::Color32::WHITE.gamma_multiply(*alpha as f32 / 255.),
+                        None => ui::Color32::WHITE
+                    };
+
+                    ui.image(plot::PlotImage::new
+                    (
+                        texture,
+                        plot::PlotPoint { x: pos.x as f64 + 0.5, y: pos.y as f64 + 0.5 },
+                        (1., 1.)
+                    )
+                    .uv(*uv)
+                    .tint(tint))
+                }
+            }
+
+            // annotated cells get a corner folded over, it stays visible on top of any tile
+            {
+                let visible = self.visible_cells.expect("the visible cells are set at the start of the frame");
+
+                for pos in workspace.notes.keys().filter(|pos| visible.contains(**pos))
+                {
+                    let (right, top) = (pos.x as f64 + 1., pos.y as f64 + 1.);
+
+                    ui.polygon
+                    (
+                        plot::Polygon::new(vec![[right - 0.35, top], [right, top], [right, top - 0.35]])
+                            .fill_color(ui::Color32::from_rgb(250, 210, 80))
+                            .stroke(ui::Stroke::new(1., ui::Color32::from_gray(40)))
+                    )
+                }
+            }
+
+            // objects are drawn over the tiles, the last placed on top
+            for (idx, object) in workspace.objects.iter().enumerate()
+            {
+                let color = object.color();
+                let width = if workspace.selected_object == Some(idx) { 3. } else { 1.5 };
+
+                let label = match object.size
+                {
+                    Some((object_width, height)) =>
+                    {
+                        let (left, bottom) = (object.x, object.y);
+                        let (right, top) = (left + object_width, bottom + height);
+
+                        ui.polygon
+                        (
+                            plot::Polygon::new(vec![[left, bottom], [right, bottom], [right, top], [left, top]])
+                                .fill_color(color.gamma_multiply(0.15))
+                                .stroke(ui::Stroke::new(width, color))
+                        );
+
+                        plot::Text::new(plot::PlotPoint::new(left, top), object.kind.as_str())
+                            .anchor(ui::Align2::LEFT_BOTTOM)
+                    }
+                    None =>
+                    {
+                        ui.points
+                        (
+                            plot::Points::new(vec![[object.x, object.y]])
+                                .radius(2. + width * 1.5)
+                                .color(color)
+                        );
+
+                        plot::Text::new(plot::PlotPoint::new(object.x, object.y + 0.2), object.kind.as_str())
+                            .anchor(ui::Align2::CENTER_BOTTOM)
+                    }
+                };
+
+                ui.text(label.color(color))
+            }
+
+            if self.show_tile_outlines
+            {
+                let visible = self.visible_cells.expect("the visible cells are set at the start of the frame");
+
+                for pos in workspace.tiles.keys().filter(|pos| visible.contains(**pos))
+                {
+                    let (left, bottom) = (pos.x as f64, pos.y as f64);
+                    let (right, top) = (left + 1., bottom + 1.);
+
+                    ui.polygon
+                    (
+                        plot::Polygon::new(vec![[left, bottom], [right, bottom], [right, top], [left, top]])
+                            .fill_color(ui::Color32::TRANSPARENT)
+                            .stroke(ui::Stroke::new(1., ui::Color32::from_rgb(255, 200, 60)))
+                    )
+                }
+            }
+
+            // the cells that differ from the compared map, colored by how
+            if let Some((ref diff, _)) = workspace.diff
+            {
+                let visible = self.visible_cells.expect("the visible cells are set at the start of the frame");
+
+                for (cells, color) in [(&diff.added, DIFF_ADDED), (&diff.removed, DIFF_REMOVED), (&diff.changed, DIFF_CHANGED)]
+                {
+                    for pos in cells.iter().filter(|pos| visible.contains(**pos))
+                    {
+                        let (left, bottom) = (pos.x as f64, pos.y as f64);
+                        let (right, top) = (left + 1., bottom + 1.);
+
+                        ui.polygon
+                        (
+                            plot::Polygon::new(vec![[left, bottom], [right, bottom], [right, top], [left, top]])
+                                .fill_color(color.gamma_multiply(0.25))
+                                .stroke(ui::Stroke::new(1.5, color))
+                        )
+                    }
+                }
+            }
+
+            // the cells the last undo or redo touched fade out
+            if let Some((ref cells, since)) = workspace.history_flash
+            {
+                let fade = since.elapsed().as_secs_f32() / HISTORY_FLASH_SECONDS;
+                let color = ui::Color32::WHITE.gamma_multiply(0.5 * (1. - fade).max(0.));
+                let visible = self.visible_cells.expect("the visible cells are set at the start of the frame");
+
+                for pos in cells.iter().filter(|pos| visible.contains(**pos))
+                {
+                    let (left, bottom) = (pos.x as f64, pos.y as f64);
+                    let (right, top) = (left + 1., bottom + 1.);
+
+                    ui.polygon
+                    (
+                        plot::Polygon::new(vec![[left, bottom], [right, bottom], [right, top], [left, top]])
+                            .fill_color(color)
+                            .stroke(ui::Stroke::NONE)
+                    )
+                }
+
+                if fade < 1.
+                {
+                    ui.ctx().request_repaint()
+                }
+                else
+                {
+                    workspace.history_flash = None
+                }
+            }
+
+            if let Some(selection) = workspace.selection
+            {
+                let (min, max) = (selection.min(), selection.max());
+
+                let (left, bottom) = (min.x as f64, min.y as f64);
+                let (right, top) = (max.x as f64 + 1., max.y as f64 + 1.);
+
+                ui.polygon
+                (
+                    plot::Polygon::new(vec![[left, bottom], [right, bottom], [right, top], [left, top]])
+                        .fill_color(ui::Color32::from_rgba_unmultiplied(120, 170, 255, 25))
+                        .stroke(ui::Stroke::new(1.5, ui::Color32::from_rgb(120, 170, 255)))
+                )
+            }
+
+            if let Some(ref mask) = workspace.mask
+            {
+                let visible = self.visible_cells.expect("the visible cells are set at the start of the frame");
+
+                for pos in mask.iter().filter(|pos| visible.contains(**pos))
+                {
+                    let (left, bottom) = (pos.x as f64, pos.y as f64);
+                    let (right, top) = (left + 1., bottom + 1.);
+
+                    ui.polygon
+                    (
+                        plot::Polygon::new(vec![[left, bottom], [right, bottom], [right, top], [left, top]])
+                            .fill_color(ui::Color32::from_rgba_unmultiplied(120, 170, 255, 40))
+                            .stroke(ui::Stroke::new(1., ui::Color32::from_rgb(120, 170, 255)))
+                    )
+                }
+            }
+
+            editable
+        };
+
+        let panel_contents = |ui: &mut ui::egui::Ui|
+        {
+            let grid_spacer = || -> Box<dyn Fn(plot::GridInput) -> Vec<plot::GridMark>>
+            {
+                if grid_per_tile
+                {
+                    Box::new(tile_grid_spacer)
+                }
+                else
+                {
+                    plot::log_grid_spacer(1)
+                }
+            };
+
+            let plot = plot::Plot::new("tilemap display")
+                .data_aspect(1.)
+
+                .x_grid_spacer(grid_spacer())
+                .y_grid_spacer(grid_spacer())
+            
+                .allow_double_click_reset(false)
+                
+                .allow_drag(false)
+                .allow_boxed_zoom(false)
+                .allow_zoom(!cycling)
+                .allow_scroll(!cycling)
+                .show_background(false)
+
+                // the columns are numbered along the top and the rows along the left,
+                // each grid line is named after the cell it starts
+                .show_axes(show_coordinates && !preview)
+                .show_grid(!preview)
+                .x_axis_position(plot::VPlacement::Top)
+                .x_axis_formatter(cell_coordinate(origin_offset.0))
+                .y_axis_formatter(cell_coordinate(origin_offset.1))
+                
+                .show(ui, plot_contents);
+
+            if !plot.inner
+            {
+                ui.painter().text
+                (
+                    plot.response.rect.center_top() + ui::vec2(0., 10.),
+                    ui::Align2::CENTER_TOP,
+                    "zoom in to edit",
+                    ui::FontId::proportional(16.),
+                    ui::Color32::from_gray(200)
+                );
+            }
+        };
+
+        let canvas = ui::CentralPanel::default()
+            .frame(ui::Frame
+            {
+                inner_margin: ui::Margin::symmetric(1., 5.),
+                fill: background,
+                ..Default::default()
+            })
+            .show(app.ui().context(), panel_contents)
+            .response
+            .rect;
+
+        if self.show_texture_debug && !self.preview
+        {
+            let workspace = &self.workspaces[self.active_workspace];
+
+            let text = match (workspace.sheet_texture_id, &workspace.fallback_texture)
+            {
+                (None, _) if workspace.sprite_sheet.is_none() => "sheet texture: no sheet".to_owned(),
+                (None, _) => "sheet texture: not ready".to_owned(),
+                (Some(id), Some((.., Some(texture)))) if texture.id() == id => format!("sheet texture: {id:?}, decoded here"),
+                (Some(id), _) => format!("sheet texture: {id:?}, from the image loaders")
+            };
+
+            let layer = ui::LayerId::new(ui::Order::Foreground, ui::Id::new("texture debug"));
+
+            app.ui().context().layer_painter(layer).text
+            (
+                canvas.left_bottom() + ui::vec2(8., -8.),
+                ui::Align2::LEFT_BOTTOM,
+                text,
+                ui::FontId::monospace(12.),
+                ui::Color32::from_gray(200)
+            );
+        }
+
+        if let Some((size, bounds)) = self.canvas_view.filter(|_| !self.preview)
+        {
+            self.zoom_indicator(app, canvas, size.x as f64 / bounds.width())
+        }
+    }
+
+    /// writes in the corner of the canvas how big the tiles are on screen next to their size in the sheet,
+    /// along with a bar a round amount of cells long, clicking it shows the sheet pixels at their size
+    fn zoom_indicator(&mut self, app: &mut app::App, canvas: ui::Rect, cell_points: f64)
+    {
+        let ctx = app.ui().context();
+        let workspace = &self.workspaces[self.active_workspace];
+
+        // a hundred percent shows every pixel of the sheet as a pixel of the screen
+        let pixels_per_point = ctx.pixels_per_point() as f64;
+        let tile_pixels = workspace.sheet_dimensions
+            .zip(workspace.sprite_sheet.as_ref())
+            .map(|((width, _), sprite_sheet)| width as f64 / sprite_sheet.rows.max(1) as f64)
+            .filter(|tile_pixels| *tile_pixels >= 1.);
+
+        // the roundest amount of cells that makes a bar of a readable length, none once a single cell is too long
+        let bar_cells = [1, 2, 5, 10, 20, 50, 100, 200, 500, 1000]
+            .into_iter()
+            .find(|cells| SCALE_BAR_POINTS.contains(&(*cells as f64 * cell_points)));
+
+        let clicked = ui::Area::new(ui::Id::new("zoom indicator"))
+            .pivot(ui::Align2::RIGHT_BOTTOM)
+            .fixed_pos(canvas.right_bottom() + ui::vec2(-8., -8.))
+            .show(ctx, |ui|
+            {
+                ui::Frame::popup(ui.style()).show(ui, |ui|
+                {
+                    ui.horizontal(|ui|
+                    {
+                        if let Some(cells) = bar_cells
+                        {
+                            let (rect, _) = ui.allocate_exact_size(ui::vec2((cells as f64 * cell_points) as f32, 8.), ui::Sense::hover());
+                            let stroke = ui::Stroke::new(1.5, ui.visuals().text_color());
+
+                            ui.painter().line_segment([rect.left_bottom(), rect.right_bottom()], stroke);
+                            ui.painter().line_segment([rect.left_top(), rect.left_bottom()], stroke);
+                            ui.painter().line_segment([rect.right_top(), rect.right_bottom()], stroke);
+
+                            ui.label(match cells
+                            {
+                                1 => "1 cell".to_owned(),
+                                cells => format!("{cells} cells")
+                            });
+
+                            ui.separator();
+                        }
+
+                        let zoom = match tile_pixels
+                        {
+                            Some(tile_pixels) => format!("{:.0}%", cell_points * pixels_per_point / tile_pixels * 100.),
+                            None => format!("{:.1} px per cell", cell_points * pixels_per_point)
+                        };
+
+                        ui.add(ui::Label::new(ui::RichText::new(zoom).monospace()).sense(ui::Sense::click()))
+                            .on_hover_text("click to show the pixels of the sheet at their size")
+                            .clicked()
+                    })
+                    .inner
+                })
+                .inner
+            })
+            .inner;
+
+        if clicked
+        {
+            self.zoom_reset = tile_pixels.map(|tile_pixels| tile_pixels / pixels_per_point)
+        }
+    }
+
+    fn check_input(&mut self, app: &mut app::App)
+    {
+        if app.input.get_key_down(input::KeyCode::KeyZ)
+            && app.input.get_key_holding
+            (
+                input::KeyCode::ControlLeft
+            )
+            && !app.input.get_key_holding
+            (
+                input::KeyCode::ShiftLeft
+            )
+        {
+            self.workspace_mut().undo()
+        }
         
         if app.input.get_key_down(input::KeyCode::KeyZ)
             && app.input.get_key_holding
@@ -432,176 +4774,1855 @@ impl Application
                 input::KeyCode::ShiftLeft
             )
         {
-            let Some(redo_tiles) = self.redos.pop() else
+            self.workspace_mut().redo()
+        }
+
+        if app.input.get_key_down(input::KeyCode::KeyS)
+            && app.input.get_key_holding(input::KeyCode::ControlLeft)
+        {
+            self.save_workspace()
+        }
+
+        // a focused text field copies and pastes its own text
+        if app.input.get_key_holding(input::KeyCode::ControlLeft) && !app.ui().context().wants_keyboard_input()
+        {
+            self.check_clipboard_input(app)
+        }
+
+        if app.input.get_key_down(input::KeyCode::KeyP)
+            && app.input.get_key_holding(input::KeyCode::ControlLeft)
+        {
+            self.command_filter = Some(String::new())
+        }
+
+        if app.input.get_key_down(input::KeyCode::KeyG)
+            && app.input.get_key_holding(input::KeyCode::ControlLeft)
+            && self.workspace().sprite_sheet.is_some()
+        {
+            self.tile_index_entry = Some(String::new())
+        }
+
+        if app.input.get_key_down(input::KeyCode::Escape)
+        {
+            self.workspace_mut().deselect()
+        }
+
+        if app.input.get_key_down(input::KeyCode::Tab) && !app.ui().context().wants_keyboard_input()
+        {
+            self.toggle_preview()
+        }
+
+        // the arrows would also move the text cursor of a focused field
+        if !app.ui().context().wants_keyboard_input()
+        {
+            let nudges =
+            [
+                (input::KeyCode::ArrowLeft, -1, 0),
+                (input::KeyCode::ArrowRight, 1, 0),
+                (input::KeyCode::ArrowUp, 0, 1),
+                (input::KeyCode::ArrowDown, 0, -1)
+            ];
+
+            for (key, x, y) in nudges
+            {
+                if app.input.get_key_down(key)
+                {
+                    self.workspace_mut().nudge_selection(TilePos { x, y })
+                }
+            }
+        }
+    }
+
+    fn toggle_preview(&mut self)
+    {
+        self.preview = !self.preview;
+
+        // a stroke can't be finished once the canvas stops taking clicks
+        self.workspace_mut().cancel_drag();
+
+        if self.preview
+        {
+            self.toast = Some(("preview, tab to go back".to_owned(), std::time::Instant::now()))
+        }
+    }
+
+    fn check_clipboard_input(&mut self, app: &mut app::App)
+    {
+        let copy = app.input.get_key_down(input::KeyCode::KeyC);
+        let cut = app.input.get_key_down(input::KeyCode::KeyX);
+
+        if copy || cut
+        {
+            self.copy(cut)
+        }
+
+        if app.input.get_key_down(input::KeyCode::KeyV)
+        {
+            self.paste()
+        }
+    }
+
+    /// copies the selected tiles, removing them from the map if `cut`
+    fn copy(&mut self, cut: bool)
+    {
+        let Some(copied) = self.workspace().copy_selection() else
+        {
+            return
+        };
+
+        // the in app copy is still there if the system clipboard can't be reached
+        copied.to_system();
+        self.clipboard = Some(copied);
+
+        if cut
+        {
+            self.workspace_mut().delete_selection()
+        }
+    }
+
+    /// pastes under the cursor around the stamp anchor,
+    /// or over the selection when the canvas isn't hovered
+    fn paste(&mut self)
+    {
+        // the system clipboard wins unless it holds something that isn't tiles
+        let Some(copied) = clipboard::ClipboardTiles::from_system().or(self.clipboard.clone()) else
+        {
+            return
+        };
+
+        let anchored = self.hovered_cell.map(|cell|
+        {
+            let offset = self.stamp_anchor.top_left_offset(copied.width, copied.height);
+
+            TilePos { x: cell.x + offset.x, y: cell.y + offset.y }
+        });
+
+        let selected_corner = self.workspace().selection_bounds().map(|selection| selection.top_left());
+
+        let Some(pos) = anchored.or(selected_corner) else
+        {
+            return
+        };
+
+        self.workspace_mut().paste(&copied, pos)
+    }
+
+    fn load_workspace(&mut self)
+    {
+        let Some(worskspace_path) = file_dialog(self.workspace_folder.as_deref())
+            .add_filter("", &["bag"])
+            .set_file_name("load spritesheet data")
+            .pick_file()
+        else
+        {
+            return
+        };
+
+        self.workspace_folder = worskspace_path.parent().map(PathBuf::from);
+        self.open_saved(worskspace_path)
+    }
+
+    /// starts reading the workspace saved at `path`, it opens in a new tab once it's all read,
+    /// or the toast tells why it couldn't be
+    fn open_saved(&mut self, path: PathBuf)
+    {
+        // the workspace is already open, just show its tab
+        if let Some(idx) = self.workspaces
+            .iter()
+            .position(|workspace| workspace.workspace_path.as_ref() == Some(&path))
+        {
+            self.active_workspace = idx;
+            return
+        }
+
+        if self.workspace_load.as_ref().is_some_and(|workspace_load| workspace_load.path == path)
+        {
+            return
+        }
+
+        self.workspace_load = Some(loading::WorkspaceLoad::start(path))
+    }
+
+    /// shows the progress of the workspace being read, and opens it once it's done
+    fn workspace_load_window(&mut self, app: &mut app::App)
+    {
+        let Some(ref workspace_load) = self.workspace_load else
+        {
+            return
+        };
+
+        match workspace_load.poll()
+        {
+            Some(Ok(saved)) =>
+            {
+                let path = workspace_load.path.clone();
+                self.workspace_load = None;
+                self.open_workspace(Workspace::from_saved(path, saved))
+            }
+            Some(Err(err)) =>
+            {
+                self.toast = Some((format!("couldn't open {}: {err}", workspace_load.path.display()), std::time::Instant::now()));
+                self.workspace_load = None
+            }
+            None =>
+            {
+                let progress = workspace_load.progress();
+                let name = workspace_load.path.file_name().unwrap_or_default().to_string_lossy();
+
+                // the tab only shows up once every tile is in, a half read map is never edited
+                ui::Window::new("opening workspace")
+                    .collapsible(false)
+                    .resizable(false)
+                    .show(app.ui().context(), |ui|
+                    {
+                        ui.label(name);
+                        ui.add(ui::ProgressBar::new(progress).show_percentage())
+                    });
+
+                app.ui().context().request_repaint()
+            }
+        }
+    }
+
+    /// saves the current workspace, telling where it went once it's done
+    fn save_workspace(&mut self)
+    {
+        let folder = self.workspace_folder.clone();
+
+        if self.workspace_mut().save(folder.as_deref()).is_err()
+        {
+            return
+        }
+
+        if let Some(path) = self.workspace().workspace_path.clone()
+        {
+            self.workspace_folder = path.parent().map(PathBuf::from);
+            self.toast = Some((format!("saved to {}", path.display()), std::time::Instant::now()))
+        }
+    }
+
+    /// writes the preferences if anything they hold changed this frame
+    fn save_preferences(&mut self)
+    {
+        let workspace = self.workspace();
+
+        let last_tile = match (workspace.selected_tile, &workspace.sprite_sheet)
+        {
+            (Some((idx, _)), Some(sprite_sheet)) => Some((sprite_sheet.path.clone(), idx)),
+            _ => self.preferences.last_tile.clone()
+        };
+
+        let preferences = preferences::Preferences
+        {
+            tool: self.current_tool,
+            brush_size: self.max_brush_size,
+            brush_density: self.brush_density,
+            brush_shape: self.brush_shape,
+            hollow_shapes: self.hollow_shapes,
+            shape_border: self.shape_border,
+            paint_mode: self.paint_mode,
+            magnetic_placement: self.magnetic_placement,
+            snap_to_super_grid: self.snap_to_super_grid,
+            super_grid_size: self.super_grid_size,
+            stamp_anchor: self.stamp_anchor,
+            secondary_button: self.secondary_button,
+            auto_reload_sheet: self.auto_reload_sheet,
+
+            sheet_folder: self.sheet_folder.clone(),
+            workspace_folder: self.workspace_folder.clone(),
+
+            last_tile
+        };
+
+        if preferences != self.preferences
+        {
+            let _ = preferences.save();
+            self.preferences = preferences
+        }
+    }
+
+    /// asks for a folder and writes the json index grid of every workspace saved in it
+    fn convert_folder(&mut self)
+    {
+        if let Some(folder) = file_dialog(self.workspace_folder.as_deref()).pick_folder()
+        {
+            self.batch_results = None;
+            self.folder_conversion = Some(export::FolderConversion::start(&folder, &self.grid_export.empty_cell))
+        }
+    }
+
+    /// asks for a folder and lists the workspaces saved in it
+    fn browse_projects(&mut self)
+    {
+        if let Some(folder) = file_dialog(self.workspace_folder.as_deref()).pick_folder()
+        {
+            self.workspace_folder = Some(folder.clone());
+            self.project_browser = Some(browser::ProjectBrowser::scan(folder))
+        }
+    }
+}
+
+impl Workspace
+{
+    fn new() -> Self
+    {
+        Self::with_settings(settings::ProjectSettings::default())
+    }
+
+    fn with_settings(settings: settings::ProjectSettings) -> Self
+    {
+        Self
+        {
+            sprite_sheet: None,
+            workspace_path: None,
+            selected_tile: None,
+            recent_tiles: Default::default(),
+            variant_tiles: Vec::new(),
+            palette_images: [Vec::new(), Vec::new()],
+            palette_key: [EMPTY_PALETTE_KEY, EMPTY_PALETTE_KEY],
+
+            tiles: Tiles::default(),
+            notes: HashMap::new(),
+            opacity: HashMap::new(),
+            objects: Vec::new(),
+            selected_object: None,
+            object_drag: None,
+            undos: TilesHistory::new(settings.history_depth),
+            redos: TilesHistory::new(settings.history_depth),
+            history_flash: None,
+            operations: Vec::new(),
+            dirty: false,
+
+            secondary_tile: None,
+            sheet_dimensions: None,
+            sheet_unreadable: false,
+            sheet_error: None,
+            sheet_modified: None,
+            sheet_changed: false,
+            sheet_watcher: None,
+            missing_tiles: Vec::new(),
+            fallback_texture: None,
+            sheet_texture_id: None,
+            fit_palette: false,
+            blank_tiles: None,
+            blank_tiles_search: None,
+            tile_slicing: None,
+
+            dragging: None,
+            stroke_brush: None,
+            touched_cells: HashSet::new(),
+            shape_drag: None,
+
+            selection: None,
+            mask: None,
+            selecting: false,
+            moving: None,
+            stamp_capture: None,
+            stamp: None,
+            diff: None,
+
+            settings,
+        }
+    }
+
+    /// selects a tile and remembers it as the most recently used one
+    fn select_tile(&mut self, idx: usize, uv: ui::Rect)
+    {
+        self.selected_tile = Some((idx, uv));
+        self.stamp = None;
+
+        self.recent_tiles.retain(|recent| *recent != idx);
+        self.recent_tiles.push_front(idx);
+        self.recent_tiles.truncate(RECENT_TILES)
+    }
+
+    /// points every placed tile back at the index it had with the `from` rows and columns,
+    /// tiles whose index doesn't exist anymore are left alone
+    ///
+    /// every uv in the history was picked with the old rows and columns, undoing across the remap
+    /// would put them back under the new ones, so the history is cleared instead
+    fn remap_tiles(&mut self, from: (usize, usize))
+    {
+        let Some(SpriteSheet { rows, columns, .. }) = self.sprite_sheet else
+        {
+            return
+        };
+
+        let remapped = self.tiles
+            .iter()
+            .filter_map(|(pos, uv)| tile_index(*uv, from.0, from.1).map(|idx| (*pos, idx)))
+            .filter(|(_, idx)| *idx < rows * columns)
+            .map(|(pos, idx)| (pos, tile_uv(idx, rows, columns)))
+            .collect::<Tiles>();
+
+        self.operations.push(oplog::Operation::Paint
+        {
+            tiles: remapped.iter().map(|(pos, uv)| (*pos, *uv)).collect()
+        });
+
+        let remapped = remapped
+            .into_iter()
+            .map(|(pos, uv)| (pos, uv, self.opacity_at(pos)))
+            .collect::<Vec<_>>();
+
+        self.apply(remapped);
+
+        self.dirty = true;
+        self.undos.clear();
+        self.redos.clear();
+
+        // the palette selections keep their index too
+        for tile in [&mut self.selected_tile, &mut self.secondary_tile]
+        {
+            *tile = tile
+                .filter(|(idx, ..)| *idx < rows * columns)
+                .map(|(idx, ..)| (idx, tile_uv(idx, rows, columns)))
+        }
+
+        self.tile_slicing = Some((rows, columns))
+    }
+
+    /// the uvs of the ctrl clicked variants that the sheet has
+    fn variant_uvs(&self) -> Vec<ui::Rect>
+    {
+        let Some(&SpriteSheet { rows, columns, .. }) = self.sprite_sheet.as_ref() else
+        {
+            return Vec::new()
+        };
+
+        self.variant_tiles
+            .iter()
+            .filter(|idx| **idx < rows * columns)
+            .map(|idx| tile_uv(*idx, rows, columns))
+            .collect()
+    }
+
+    /// swaps the selected tile for one of the variants picked at random wherever it's placed,
+    /// only inside the selection or the mask if there is one
+    fn shuffle_variants(&mut self, rng: &mut rng::Rng)
+    {
+        let (Some((_, target)), Some(&SpriteSheet { rows, columns, .. })) = (self.selected_tile, self.sprite_sheet.as_ref()) else
+        {
+            return
+        };
+
+        let variants = self.variant_tiles
+            .iter()
+            .filter(|idx| **idx < rows * columns)
+            .copied()
+            .collect::<Vec<_>>();
+
+        if variants.is_empty()
+        {
+            return
+        }
+
+        let anywhere = self.selection_bounds().is_none();
+
+        let shuffled = self.tiles
+            .iter()
+            .filter(|(pos, uv)| **uv == target && (anywhere || self.in_selection(**pos)))
+            .map(|(pos, _)| (*pos, tile_uv(variants[rng.below(variants.len())], rows, columns)))
+            .collect::<Tiles>();
+
+        let operation = oplog::Operation::Paint { tiles: shuffled.iter().map(|(pos, uv)| (*pos, *uv)).collect() };
+        self.edit(shuffled, operation)
+    }
+
+    /// moves the selection `step` places through the recently used tiles,
+    /// without reordering them so going back and forth stays predictable
+    fn cycle_recent_tiles(&mut self, step: isize)
+    {
+        let Some(ref sprite_sheet) = self.sprite_sheet else
+        {
+            return
+        };
+
+        if self.recent_tiles.is_empty()
+        {
+            return
+        }
+
+        let current = self.selected_tile
+            .and_then(|(idx, ..)| self.recent_tiles.iter().position(|recent| *recent == idx))
+            .unwrap_or_default();
+
+        let len = self.recent_tiles.len() as isize;
+        let idx = self.recent_tiles[(current as isize + step).rem_euclid(len) as usize];
+
+        self.selected_tile = Some((idx, tile_uv(idx, sprite_sheet.rows, sprite_sheet.columns)))
+    }
+
+    /// true if nothing has been chosen or drawn in this workspace yet
+    fn is_untouched(&self) -> bool
+    {
+        self.sprite_sheet.is_none() && self.workspace_path.is_none() && self.tiles.is_empty() && self.notes.is_empty()
+            && self.objects.is_empty()
+    }
+
+    /// the name shown on the tab
+    fn title(&self) -> String
+    {
+        let path = self.workspace_path
+            .as_ref()
+            .or(self.sprite_sheet.as_ref().map(|sprite_sheet| &sprite_sheet.path));
+
+        match path.and_then(|path| path.file_stem())
+        {
+            Some(name) => name.to_string_lossy().into_owned(),
+            None => "untitled".to_owned()
+        }
+    }
+
+    /// the size the save file would have with the current tiles
+    fn estimated_save_size(&self) -> u64
+    {
+        // every tile takes the same amount of bytes, so only the first one is measured
+        let tile_size = save_format().serialized_size(&(TilePos { x: 0, y: 0 }, ui::Rect::NOTHING))
+            .unwrap_or_default();
+
+        let sprite_sheet_size = self.sprite_sheet
+            .as_ref()
+            .and_then(|sprite_sheet| save_format().serialized_size(sprite_sheet).ok())
+            .unwrap_or_default();
+
+        // the tiles are saved as a vec, which starts with its length
+        let length_size = save_format().serialized_size(&0usize).unwrap_or_default();
+
+        let notes_size = save_format().serialized_size(&self.notes).unwrap_or_default();
+        let objects_size = save_format().serialized_size(&self.objects).unwrap_or_default();
+        let opacity_size = save_format().serialized_size(&self.opacity).unwrap_or_default();
+
+        sprite_sheet_size + length_size + tile_size * self.tiles.len() as u64 + notes_size + objects_size + opacity_size
+    }
+
+    /// swaps all the tiles with `tiles` as a single undoable edit
+    fn replace_tiles(&mut self, tiles: Tiles)
+    {
+        // the old value of every cell we are about to touch,
+        // cells that were empty are marked as nothing
+        let mut undo_tiles = std::mem::take(&mut self.tiles);
+
+        // the replaced map brings no opacity of its own
+        let opacity = std::mem::take(&mut self.opacity);
+
+        self.operations.push(oplog::Operation::Replace
+        {
+            tiles: tiles.iter().map(|(pos, uv)| (*pos, *uv)).collect()
+        });
+
+        for pos in tiles.keys()
+        {
+            undo_tiles.entry(*pos).or_insert(ui::Rect::NOTHING);
+        }
+
+        self.tiles = tiles;
+
+        self.dirty = true;
+        self.redos.clear();
+        self.undos.add(undo_tiles
+            .into_iter()
+            .map(|(pos, uv)| (pos, uv, opacity.get(&pos).copied().unwrap_or(u8::MAX)))
+        )
+    }
+
+    /// the alpha the tile at `pos` is drawn with
+    fn opacity_at(&self, pos: TilePos) -> u8
+    {
+        self.opacity.get(&pos).copied().unwrap_or(u8::MAX)
+    }
+
+    /// draws the tile at `pos` with `alpha`, fully opaque ones aren't kept
+    fn set_opacity_at(&mut self, pos: TilePos, alpha: u8)
+    {
+        match alpha
+        {
+            u8::MAX => self.opacity.remove(&pos),
+            alpha => self.opacity.insert(pos, alpha)
+        };
+    }
+
+    /// writes `tiles` on the map with their opacity, where nothing means the cell gets emptied,
+    /// and returns the values they replaced
+    fn apply(&mut self, tiles: impl IntoIterator<Item = (TilePos, ui::Rect, u8)>) -> Vec<(TilePos, ui::Rect, u8)>
+    {
+        let tiles = tiles.into_iter();
+        let mut replaced = Vec::with_capacity(tiles.size_hint().0);
+
+        for (pos, uv, alpha) in tiles
+        {
+            let old_alpha = self.opacity_at(pos);
+
+            // swap removing keeps this constant time, the order of a grid doesn't matter
+            let old_uv = if uv == ui::Rect::NOTHING
+            {
+                self.opacity.remove(&pos);
+                self.tiles.swap_remove(&pos)
+            }
+            else
+            {
+                self.set_opacity_at(pos, alpha);
+                self.tiles.insert(pos, uv)
+            };
+
+            replaced.push((pos, old_uv.unwrap_or(ui::Rect::NOTHING), old_alpha));
+        }
+
+        replaced
+    }
+
+    /// applies `tiles` as a single undoable edit, logged as `operation`,
+    /// the cells keep the opacity they had
+    fn edit(&mut self, tiles: Tiles, operation: oplog::Operation)
+    {
+        self.edit_with_opacity(tiles, None, operation)
+    }
+
+    /// applies `tiles` like `edit_with_opacity`, as new tiles put down by a paint, stamp, paste or fill,
+    /// so the overflow policy decides what happens to the ones past the coordinate limit
+    fn place(&mut self, mut tiles: Tiles, alpha: Option<u8>, operation: oplog::Operation)
+    {
+        let placed = tiles
+            .iter()
+            .filter(|(_, uv)| **uv != ui::Rect::NOTHING)
+            .map(|(pos, _)| *pos)
+            .collect::<Vec<_>>();
+
+        if !self.settings.admit(&placed)
+        {
+            return
+        }
+
+        // nothing is placed past the coordinate limit, though erasing there is fine
+        let placing = tiles.len();
+        tiles.retain(|pos, uv| *uv == ui::Rect::NOTHING || self.settings.in_bounds(*pos));
+
+        let operation = if tiles.len() < placing
+        {
+            operation.clipped(&tiles)
+        }
+        else
+        {
+            operation
+        };
+
+        self.edit_with_opacity(tiles, alpha, operation)
+    }
+
+    /// applies `tiles` as a single undoable edit, logged as `operation`,
+    /// the painted cells get drawn with `alpha` if there's one
+    ///
+    /// the coordinate limit isn't checked, so edits rewriting the tiles already on the map reach all of them
+    fn edit_with_opacity(&mut self, mut tiles: Tiles, alpha: Option<u8>, operation: oplog::Operation)
+    {
+        // cells that already hold the value would only bloat the history
+        tiles.retain(|pos, uv|
+        {
+            let repainted = *uv != ui::Rect::NOTHING && alpha.is_some_and(|alpha| alpha != self.opacity_at(*pos));
+
+            self.tiles.get(pos).copied().unwrap_or(ui::Rect::NOTHING) != *uv || repainted
+        });
+
+        if tiles.is_empty()
+        {
+            return
+        }
+
+        self.operations.push(operation);
+
+        let tiles = tiles
+            .into_iter()
+            .map(|(pos, uv)| (pos, uv, alpha.unwrap_or_else(|| self.opacity_at(pos))))
+            .collect::<Vec<_>>();
+
+        let undo_tiles = self.apply(tiles);
+
+        self.dirty = true;
+        self.redos.clear();
+        self.undos.add(undo_tiles)
+    }
+
+    fn undo(&mut self)
+    {
+        // a stroke in progress already remembers the cells as they are,
+        // changing them under it would make its undo restore the wrong values
+        if self.dragging.is_some()
+        {
+            return
+        }
+
+        let Some(undo_tiles) = self.undos.pop() else 
+        {
+            return
+        };
+
+        self.operations.push(oplog::Operation::undo(&written(&undo_tiles)));
+        self.flash_history(&undo_tiles);
+
+        // here we will gather the tiles we are replacing with the undo tiles,
+        // so that we can use them as redo operation later
+        let redo_tiles = self.apply(undo_tiles.into_vec());
+
+        self.dirty = true;
+        self.redos.add(redo_tiles)
+    }
+
+    fn redo(&mut self)
+    {
+        if self.dragging.is_some()
+        {
+            return
+        }
+
+        let Some(redo_tiles) = self.redos.pop() else
+        {
+            return
+        };
+
+        self.operations.push(oplog::Operation::redo(&written(&redo_tiles)));
+        self.flash_history(&redo_tiles);
+
+        // here we will gather the tiles we are replacing with the redo tiles,
+        // so that we can use them as undo operation later
+        let undo_tiles = self.apply(redo_tiles.into_vec());
+
+        self.dirty = true;
+        self.undos.add(undo_tiles)
+    }
+
+    /// highlights the cells of `delta` for a moment
+    fn flash_history(&mut self, delta: &[(TilePos, ui::Rect, u8)])
+    {
+        let cells = delta.iter().map(|(pos, ..)| *pos).collect();
+        self.history_flash = Some((cells, std::time::Instant::now()))
+    }
+
+    /// empties the whole map as a single undoable edit
+    fn clear(&mut self)
+    {
+        let erased = self.tiles
+            .keys()
+            .map(|pos| (*pos, ui::Rect::NOTHING))
+            .collect::<Tiles>();
+
+        let operation = oplog::Operation::stroke(erased.keys().copied(), ui::Rect::NOTHING);
+        self.edit(erased, operation)
+    }
+
+    /// covers every cell of `area` with `uv` as a single undoable edit
+    fn fill(&mut self, area: Selection, uv: ui::Rect)
+    {
+        let (min, max) = (area.min(), area.max());
+
+        let filled = (min.y..=max.y)
+            .flat_map(|y| (min.x..=max.x).map(move |x| (TilePos { x, y }, uv)))
+            .collect();
+
+        self.place(filled, None, oplog::Operation::fill(area, uv))
+    }
+
+    /// true if `pos` is in the mask, or in the rectangle while there's no mask
+    fn in_selection(&self, pos: TilePos) -> bool
+    {
+        match self.mask
+        {
+            Some(ref mask) => mask.contains(&pos),
+            None => self.selection.is_some_and(|selection| selection.contains(pos))
+        }
+    }
+
+    /// the rectangle around the selected cells, `None` if nothing is selected
+    fn selection_bounds(&self) -> Option<Selection>
+    {
+        let Some(ref mask) = self.mask else
+        {
+            return self.selection
+        };
+
+        let mut cells = mask.iter().copied();
+        let first = cells.next()?;
+
+        Some(cells.fold(Selection { start: first, end: first }, |bounds, pos| Selection
+        {
+            start: TilePos { x: bounds.start.x.min(pos.x), y: bounds.start.y.min(pos.y) },
+            end: TilePos { x: bounds.end.x.max(pos.x), y: bounds.end.y.max(pos.y) }
+        }))
+    }
+
+    /// the placed tiles in the selection
+    fn selected_tiles(&self) -> Tiles
+    {
+        self.tiles
+            .iter()
+            .filter(|(pos, _)| self.in_selection(**pos))
+            .map(|(pos, uv)| (*pos, *uv))
+            .collect()
+    }
+
+    /// drops both the rectangle and the mask
+    fn deselect(&mut self)
+    {
+        self.selection = None;
+        self.mask = None
+    }
+
+    /// selects every cell holding the same tile as `pos`, replacing the rectangle
+    fn select_matching(&mut self, pos: TilePos)
+    {
+        let Some(&target) = self.tiles.get(&pos) else
+        {
+            return
+        };
+
+        let mask = self.tiles
+            .iter()
+            .filter(|(_, uv)| **uv == target)
+            .map(|(pos, _)| *pos)
+            .collect();
+
+        self.selection = None;
+        self.mask = Some(mask)
+    }
+
+    /// copies the selected tiles, `None` if nothing is selected
+    fn copy_selection(&self) -> Option<clipboard::ClipboardTiles>
+    {
+        self.selection_bounds().map(|bounds| clipboard::ClipboardTiles::copy(&self.selected_tiles(), &bounds))
+    }
+
+    /// paints every selected tile over with `uv` as a single undoable edit, leaving the empty cells empty
+    fn replace_selection(&mut self, uv: ui::Rect)
+    {
+        let replaced = self.selected_tiles()
+            .into_keys()
+            .filter(|pos| self.tiles.get(pos) != Some(&uv))
+            .map(|pos| (pos, uv))
+            .collect::<Tiles>();
+
+        if replaced.is_empty()
+        {
+            return
+        }
+
+        let operation = oplog::Operation::stroke(replaced.keys().copied(), uv);
+        self.edit(replaced, operation)
+    }
+
+    /// empties every selected cell as a single undoable edit
+    fn delete_selection(&mut self)
+    {
+        if self.selection_bounds().is_none()
+        {
+            return
+        }
+
+        let erased = self.selected_tiles()
+            .into_keys()
+            .map(|pos| (pos, ui::Rect::NOTHING))
+            .collect::<Tiles>();
+
+        let operation = oplog::Operation::stroke(erased.keys().copied(), ui::Rect::NOTHING);
+        self.edit(erased, operation)
+    }
+
+    /// moves the selected tiles and the selection itself by `offset` as a single undoable edit,
+    /// the tiles they land on are replaced
+    fn nudge_selection(&mut self, offset: TilePos)
+    {
+        if self.selection_bounds().is_none()
+        {
+            return
+        }
+
+        let moved = self.selected_tiles().into_iter().collect::<Vec<_>>();
+
+        // tiles pushed past the coordinate limit would be lost, so the whole nudge is refused
+        if moved.iter().any(|(pos, _)| !self.settings.in_bounds(TilePos { x: pos.x + offset.x, y: pos.y + offset.y }))
+        {
+            return
+        }
+
+        // the cells left behind are emptied first, so tiles landing on them win
+        let mut nudged = moved
+            .iter()
+            .map(|(pos, _)| (*pos, ui::Rect::NOTHING))
+            .collect::<Tiles>();
+
+        for (pos, uv) in moved
+        {
+            nudged.insert(TilePos { x: pos.x + offset.x, y: pos.y + offset.y }, uv);
+        }
+
+        let written = nudged.iter().map(|(pos, uv)| (*pos, *uv)).collect::<Vec<_>>();
+        self.edit(nudged, oplog::Operation::nudge(&written));
+
+        let shift = |pos: TilePos| TilePos { x: pos.x + offset.x, y: pos.y + offset.y };
+
+        self.selection = self.selection.map(|selection| Selection { start: shift(selection.start), end: shift(selection.end) });
+        self.mask = self.mask.take().map(|mask| mask.into_iter().map(shift).collect())
+    }
+
+    /// drops the stroke, shape, selection or move being dragged,
+    /// the cells a stroke already painted go back to what they were without an undo entry
+    fn cancel_drag(&mut self)
+    {
+        if let Some(painted) = self.dragging.take()
+        {
+            self.apply(painted.into_iter().map(|(pos, (uv, alpha))| (pos, uv, alpha)));
+        }
+
+        self.stroke_brush = None;
+        self.touched_cells.clear();
+        self.shape_drag = None;
+        self.moving = None;
+        self.stamp_capture = None;
+        self.object_drag = None;
+        self.selecting = false
+    }
+
+    /// the tiles past the coordinate limit or far from the middle of the map,
+    /// the furthest first
+    fn stray_tiles(&self) -> Vec<TilePos>
+    {
+        let median = |mut values: Vec<i32>|
+        {
+            values.sort_unstable();
+            values.get(values.len() / 2).copied().unwrap_or_default()
+        };
+
+        let center = TilePos
+        {
+            x: median(self.tiles.keys().map(|pos| pos.x).collect()),
+            y: median(self.tiles.keys().map(|pos| pos.y).collect())
+        };
+
+        let distance = |pos: &TilePos| (pos.x - center.x).unsigned_abs().max((pos.y - center.y).unsigned_abs());
+
+        let mut strays = self.tiles
+            .keys()
+            .filter(|pos| !self.settings.in_bounds(**pos) || distance(pos) > STRAY_DISTANCE)
+            .copied()
+            .collect::<Vec<_>>();
+
+        strays.sort_by_key(|pos| std::cmp::Reverse(distance(pos)));
+        strays
+    }
+
+    /// the texture of the spritesheet from the image loaders,
+    /// or decoded here with the image crate when they report an error for it or a color key is set
+    fn sheet_texture(&mut self, ctx: &ui::Context, options: ui::TextureOptions) -> Option<ui::TextureId>
+    {
+        let path = self.sprite_sheet.as_ref()?.path.clone();
+        let color_key = self.settings.color_key;
+
+        if let Some((ref fallback_path, fallback_options, fallback_key, ref texture)) = self.fallback_texture
+        {
+            if *fallback_path == path && fallback_options == options && fallback_key == color_key
+            {
+                return texture.as_ref().map(ui::TextureHandle::id)
+            }
+        }
+
+        // the image loaders never hand out the pixels, so a keyed sheet is always decoded here
+        if color_key.is_none()
+        {
+            match ctx.try_load_texture(&sheet_uri(&path), options, Default::default())
+            {
+                Ok(ui::load::TexturePoll::Ready { texture }) => return Some(texture.id),
+                Ok(ui::load::TexturePoll::Pending { .. }) => return None,
+                Err(_) => ()
+            }
+        }
+
+        let texture = open_sheet(&path, color_key).ok().map(|image|
+        {
+            let size = [image.width() as usize, image.height() as usize];
+
+            ctx.load_texture("sheet fallback", ui::ColorImage::from_rgba_unmultiplied(size, image.as_raw()), options)
+        });
+
+        let id = texture.as_ref().map(ui::TextureHandle::id);
+        self.fallback_texture = Some((path, options, color_key, texture));
+
+        // the palette images were made from the uri that just failed, or without the key
+        self.palette_key = [EMPTY_PALETTE_KEY, EMPTY_PALETTE_KEY];
+
+        id
+    }
+
+    /// switches to the spritesheet at `path` sliced in tiles of the same size in pixels,
+    /// placed tiles keep their index and the ones the new sheet is too small for are listed in `missing_tiles`
+    fn swap_sheet(&mut self, path: PathBuf)
+    {
+        let Some(SpriteSheet { path: ref old_path, rows, columns }) = self.sprite_sheet else
+        {
+            return
+        };
+
+        let (width, height) = match image::image_dimensions(&path)
+        {
+            Ok(dimensions) => dimensions,
+            Err(err) =>
+            {
+                self.sheet_error = Some(format!("couldn't read {}: {err}", path.display()));
+                return
+            }
+        };
+
+        // without the old size the slicing is kept as is
+        let (new_rows, new_columns) = match self.sheet_dimensions.or_else(|| image::image_dimensions(old_path).ok())
+        {
+            Some((old_width, old_height)) =>
+            {
+                let tile_width = (old_width / rows.max(1) as u32).max(1);
+                let tile_height = (old_height / columns.max(1) as u32).max(1);
+
+                ((width / tile_width).max(1) as usize, (height / tile_height).max(1) as usize)
+            }
+            None => (rows, columns)
+        };
+
+        self.missing_tiles = self.tiles
+            .iter()
+            .filter_map(|(pos, uv)| tile_index(*uv, rows, columns).map(|idx| (*pos, idx)))
+            .filter(|(_, idx)| *idx >= new_rows * new_columns)
+            .collect();
+
+        self.sprite_sheet = Some(SpriteSheet { path, rows: new_rows, columns: new_columns });
+
+        self.sheet_dimensions = Some((width, height));
+        self.sheet_unreadable = false;
+        self.sheet_error = None;
+        self.sheet_modified = None;
+        self.sheet_changed = false;
+
+        // both were read from the old image
+        self.blank_tiles = None;
+        self.blank_tiles_search = None;
+        self.palette_key = [EMPTY_PALETTE_KEY, EMPTY_PALETTE_KEY];
+
+        // the same slicing leaves every uv on its index already
+        if (rows, columns) != (new_rows, new_columns)
+        {
+            self.remap_tiles((rows, columns))
+        }
+    }
+
+    /// reads the spritesheet again after it was edited elsewhere, keeping the slicing and the tiles,
+    /// the selected tiles are picked again by their index so they follow the new art
+    fn reload_sheet(&mut self, ctx: &ui::Context)
+    {
+        let Some(SpriteSheet { ref path, rows, columns }) = self.sprite_sheet else
+        {
+            return
+        };
+
+        // the image loaders keep what they read under the uri, which the same path brings back
+        ctx.forget_image(&sheet_uri(path));
+
+        self.sheet_modified = file_modified(path);
+        self.sheet_changed = false;
+
+        // all of them were read from the old pixels
+        self.sheet_dimensions = None;
+        self.sheet_unreadable = false;
+        self.fallback_texture = None;
+        self.blank_tiles = None;
+        self.blank_tiles_search = None;
+        self.palette_key = [EMPTY_PALETTE_KEY, EMPTY_PALETTE_KEY];
+
+        for tile in [&mut self.selected_tile, &mut self.secondary_tile]
+        {
+            *tile = tile
+                .filter(|(idx, ..)| *idx < rows * columns)
+                .map(|(idx, ..)| (idx, tile_uv(idx, rows, columns)))
+        }
+    }
+
+    /// points the workspace at its spritesheet moved to `path`, keeping the slicing and the tiles
+    fn relocate_sheet(&mut self, path: PathBuf)
+    {
+        let Some(ref mut sprite_sheet) = self.sprite_sheet else
+        {
+            return
+        };
+
+        match image::image_dimensions(&path)
+        {
+            Ok(dimensions) =>
+            {
+                sprite_sheet.path = path;
+
+                self.sheet_dimensions = Some(dimensions);
+                self.sheet_unreadable = false;
+                self.sheet_error = None;
+                self.sheet_modified = None;
+                self.sheet_changed = false;
+
+                // both were read from the old image
+                self.blank_tiles = None;
+                self.blank_tiles_search = None;
+                self.palette_key = [EMPTY_PALETTE_KEY, EMPTY_PALETTE_KEY]
+            }
+            Err(err) => self.sheet_error = Some(format!("couldn't read {}: {err}", path.display()))
+        }
+    }
+
+    /// places `copied` with its top left corner at `pos`
+    fn paste(&mut self, copied: &clipboard::ClipboardTiles, pos: TilePos)
+    {
+        let pasted = copied.tiles
+            .iter()
+            .map(|(offset, uv)| (TilePos { x: pos.x + offset.x, y: pos.y + offset.y }, *uv))
+            .collect::<Tiles>();
+
+        let operation = oplog::Operation::Paint { tiles: pasted.iter().map(|(pos, uv)| (*pos, *uv)).collect() };
+        self.place(pasted, None, operation)
+    }
+
+    /// writes the workspace where it was last saved, or asks where starting from `folder`
+    fn save(&mut self, folder: Option<&std::path::Path>) -> bincode::Result<()>
+    {
+        let sprite_sheet = match self.sprite_sheet
+        {
+            Some(ref sprite_sheet) => sprite_sheet.clone(),
+            None =>
+            {
+                return bincode::Result::Err
+                (
+                    Box::new(bincode::ErrorKind::Custom("no spritesheet chosen yre".to_owned()))
+                )
+            }
+        };
+
+        // create the savedata struct
+        let mut data = SavedData { sprite_sheet, tiles: Vec::new() };
+
+        // here we pass the tiles we have drawn to the vec
+        for tile in &self.tiles
+        {
+            data.tiles.push((*tile.0, *tile.1))
+        }
+
+        if self.workspace_path.is_none()
+        {
+            match file_dialog(folder)
+                    .add_filter("", &["bag"])
+                    .set_file_name("new tilemap.bag")
+                    .save_file()
+            {
+                Some(path) =>
+                {
+                    self.workspace_path = Some(path)
+                },
+                None => return bincode::Result::Err
+                (
+                    Box::new(bincode::ErrorKind::Custom("failed to crate save file".to_owned()))
+                )
+            }
+        }
+        
+        let path = self.workspace_path.as_ref().expect
+        (
+            "this call should have been unreachable as none"
+        );
+
+        // a backup that can't be made shouldn't stop the save itself
+        let _ = rotate_backups(path, self.settings.backups);
+
+        let mut file = std::fs::File::create(path)?;
+        let data = save_format().serialize(&data)?;
+        file.write_all(&data)?;
+
+        // the notes, objects and opacity follow the saved data, older versions stop reading before them
+        file.write_all(&save_format().serialize(&self.notes)?)?;
+        file.write_all(&save_format().serialize(&self.objects)?)?;
+        file.write_all(&save_format().serialize(&self.opacity)?)?;
+
+        self.settings.save(path)?;
+        self.dirty = false;
+    
+        Ok(())
+    }
+    
+    fn export_png(&self) -> image::ImageResult<()>
+    {
+        let Some(ref sprite_sheet) = self.sprite_sheet else
+        {
+            return Ok(())
+        };
+
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("", &["png"])
+            .set_file_name(format!("{}.png", self.title()))
+            .save_file()
+        else
+        {
+            return Ok(())
+        };
+
+        export::export_png(&path, &self.tiles, sprite_sheet, self.settings.color_key)
+    }
+
+    fn export_grid(&self, options: &export::GridExport) -> std::io::Result<()>
+    {
+        let Some(ref sprite_sheet) = self.sprite_sheet else
+        {
+            return Ok(())
+        };
+
+        let extension = options.format.extension();
+
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("", &[extension])
+            .set_file_name(format!("{}.{extension}", self.title()))
+            .save_file()
+        else
+        {
+            return Ok(())
+        };
+
+        let region = self.selection_bounds().filter(|_| options.selection_only);
+
+        // a mask leaves out the cells around it that hold other tiles
+        let tiles = if self.mask.is_some() && options.selection_only
+        {
+            std::borrow::Cow::Owned(self.selected_tiles())
+        }
+        else
+        {
+            std::borrow::Cow::Borrowed(&self.tiles)
+        };
+
+        export::export_grid(&path, &tiles, &self.notes, sprite_sheet, region, &self.settings, options)
+    }
+
+    fn export_walkable(&self, options: &export::WalkExport) -> std::io::Result<()>
+    {
+        let Some(ref sprite_sheet) = self.sprite_sheet else
+        {
+            return Ok(())
+        };
+
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("", &["json"])
+            .set_file_name(format!("{} walkable.json", self.title()))
+            .save_file()
+        else
+        {
+            return Ok(())
+        };
+
+        export::export_walkable(&path, &self.tiles, sprite_sheet, &self.settings, options)
+    }
+
+    /// moves an object to the end of the list, drawn over the others and picked first,
+    /// or to the start, under all of them
+    fn reorder_object(&mut self, idx: usize, to_front: bool)
+    {
+        let object = self.objects.remove(idx);
+        let new_idx = if to_front { self.objects.len() } else { 0 };
+
+        self.objects.insert(new_idx, object);
+        self.dirty = true;
+
+        // the open object window follows the object it was showing
+        self.selected_object = self.selected_object.map(|selected| match selected
+        {
+            _ if selected == idx => new_idx,
+            _ if to_front && selected > idx => selected - 1,
+            _ if !to_front && selected < idx => selected + 1,
+            _ => selected
+        })
+    }
+
+    fn export_objects(&self) -> std::io::Result<()>
+    {
+        let Some(ref sprite_sheet) = self.sprite_sheet else
+        {
+            return Ok(())
+        };
+
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("", &["json"])
+            .set_file_name(format!("{} objects.json", self.title()))
+            .save_file()
+        else
+        {
+            return Ok(())
+        };
+
+        export::export_objects(&path, &self.objects, &self.tiles, sprite_sheet)
+    }
+
+    fn export_diff(&self) -> std::io::Result<()>
+    {
+        let (Some((ref diff, _)), Some(ref sprite_sheet)) = (&self.diff, &self.sprite_sheet) else
+        {
+            return Ok(())
+        };
+
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("", &["json"])
+            .set_file_name(format!("{} diff.json", self.title()))
+            .save_file()
+        else
+        {
+            return Ok(())
+        };
+
+        diff.export(&path, &self.tiles, sprite_sheet, &self.settings)
+    }
+
+    fn export_rust(&self) -> std::io::Result<()>
+    {
+        let Some(ref sprite_sheet) = self.sprite_sheet else
+        {
+            return Ok(())
+        };
+
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("", &["rs"])
+            .set_file_name(format!("{}.rs", self.title()))
+            .save_file()
+        else
+        {
+            return Ok(())
+        };
+
+        export::export_rust(&path, &self.tiles, sprite_sheet, &self.settings)
+    }
+
+    fn export_godot(&self) -> std::io::Result<()>
+    {
+        let Some(ref sprite_sheet) = self.sprite_sheet else
+        {
+            return Ok(())
+        };
+
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("", &["tres"])
+            .set_file_name(format!("{}.tres", self.title()))
+            .save_file()
+        else
+        {
+            return Ok(())
+        };
+
+        export::export_godot(&path, &self.tiles, sprite_sheet, &self.settings)
+    }
+
+    fn export_manifest(&self) -> std::io::Result<()>
+    {
+        let Some(ref sprite_sheet) = self.sprite_sheet else
+        {
+            return Ok(())
+        };
+
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("", &["json"])
+            .set_file_name(format!("{}.slicing.json", self.title()))
+            .save_file()
+        else
+        {
+            return Ok(())
+        };
+
+        export::export_manifest(&path, sprite_sheet)
+    }
+
+    fn export_operation_log(&self) -> std::io::Result<()>
+    {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("", &["json"])
+            .set_file_name(format!("{}.log.json", self.title()))
+            .save_file()
+        else
+        {
+            return Ok(())
+        };
+
+        oplog::export(&path, self)
+    }
+
+    /// builds the workspace around what was read from the `.bag` file at `worskspace_path`
+    fn from_saved(worskspace_path: PathBuf, saved: loading::SavedMap) -> Self
+    {
+        let loading::SavedMap { sprite_sheet, tiles, notes, objects, opacity } = saved;
+
+        let mut workspace = Self::with_settings(settings::ProjectSettings::load(&worskspace_path));
+
+        // a moved or broken image would leave the map blank without a word,
+        // the tiles are kept either way so relocating the sheet brings them back
+        match image::image_dimensions(&sprite_sheet.path)
+        {
+            Ok(dimensions) => workspace.sheet_dimensions = Some(dimensions),
+            Err(err) =>
             {
-                return
-            };
+                workspace.sheet_unreadable = true;
+                workspace.sheet_error = Some(format!("couldn't read {}: {err}", sprite_sheet.path.display()))
+            }
+        }
+
+        workspace.sprite_sheet = Some(sprite_sheet);
+        workspace.workspace_path = Some(worskspace_path);
+        workspace.notes = notes;
+        workspace.opacity = opacity;
+        workspace.objects = objects;
+
+        // replaying the log of a loaded map has to start from what was saved
+        workspace.operations.push(oplog::Operation::Replace { tiles: tiles.clone() });
+
+        for (pos,uv) in tiles
+        {
+            workspace.tiles.insert(pos, uv);
+        }
+
+        workspace
+    }
+}
+
+/// moves the save at `path` to `<path>.1`, shifting the older backups up by one
+/// and removing the ones past `backups`, or the ones left over from a higher count
+fn rotate_backups(path: &std::path::Path, backups: usize) -> std::io::Result<()>
+{
+    let backup = |number: usize|
+    {
+        let mut name = path.as_os_str().to_owned();
+        name.push(format!(".{number}"));
+        PathBuf::from(name)
+    };
+
+    // the oldest one kept makes room for the one shifted onto it
+    for number in backups.max(1)..=MAX_BACKUPS
+    {
+        match std::fs::remove_file(backup(number))
+        {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err),
+            _ => ()
+        }
+    }
+
+    if backups == 0 || !path.exists()
+    {
+        return Ok(())
+    }
+
+    for number in (1..backups).rev()
+    {
+        if backup(number).exists()
+        {
+            std::fs::rename(backup(number), backup(number + 1))?
+        }
+    }
+
+    std::fs::copy(path, backup(1)).map(|_| ())
+}
+
+/// decodes the spritesheet at `path`, turning every pixel of the `color_key` color transparent
+fn open_sheet(path: &std::path::Path, color_key: Option<[u8; 3]>) -> image::ImageResult<image::RgbaImage>
+{
+    let mut sheet = image::open(path)?.into_rgba8();
+
+    if let Some(key) = color_key
+    {
+        for pixel in sheet.pixels_mut().filter(|pixel| pixel.0[..3] == key)
+        {
+            pixel.0[3] = 0
+        }
+    }
+
+    Ok(sheet)
+}
+
+/// returns the index of every tile in the spritesheet at `path` that is fully transparent,
+/// counting the pixels of the `color_key` color as transparent, empty if the image can't be read
+fn blank_tiles(path: &std::path::Path, rows: usize, columns: usize, color_key: Option<[u8; 3]>) -> HashSet<usize>
+{
+    let Ok(sheet) = open_sheet(path, color_key) else
+    {
+        return HashSet::new()
+    };
+
+    let (rows, columns) = (rows.max(1), columns.max(1));
+    let (tile_width, tile_height) = (sheet.width() / rows as u32, sheet.height() / columns as u32);
+
+    // tiles smaller than a pixel have nothing to look at
+    if tile_width == 0 || tile_height == 0
+    {
+        return HashSet::new()
+    }
+
+    (0..rows * columns)
+        .filter(|idx|
+        {
+            let (row, column) = ((idx % rows) as u32, (idx / rows) as u32);
+
+            image::imageops::crop_imm(&sheet, row * tile_width, column * tile_height, tile_width, tile_height)
+                .to_image()
+                .pixels()
+                .all(|pixel| pixel[3] == 0)
+        })
+        .collect()
+}
+
+/// every tile index sorted by how many times it's placed in `tiles`, ties keep the sheet order
+fn usage_order(tiles: &Tiles, rows: usize, columns: usize) -> Vec<usize>
+{
+    let mut uses = vec![0usize; rows * columns];
+
+    for uv in tiles.values()
+    {
+        if let Some(idx) = tile_index(*uv, rows, columns)
+        {
+            uses[idx] += 1
+        }
+    }
+
+    let mut order = (0..rows * columns).collect::<Vec<_>>();
+    order.sort_by_key(|idx| std::cmp::Reverse(uses[*idx]));
+
+    order
+}
+
+/// the images of the tiles in `indices` along with their index
+fn load_images<'a>
+(
+    source: ui::ImageSource<'a>,
+    rows: usize,
+    columns: usize,
+    indices: impl IntoIterator<Item = usize>,
+    options: ui::TextureOptions
+) -> impl Iterator<Item = (usize, ui::Image<'a>)>
+{
+    let mut items = Vec::new();
+    
+    let image = ui::Image::new(source);
+
+    for idx in indices
+    {
+        items.push
+        ((
+            idx,
+            image
+                .clone()
+                .texture_options(options)
+                .uv(tile_uv(idx, rows, columns))
+        ))
+    }
+
+    items.into_iter()
+}
+
+/// when the file at `path` was last changed, `None` if it can't be told
+fn file_modified(path: &std::path::Path) -> Option<std::time::SystemTime>
+{
+    std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+/// the file the editor was launched with, as the system passes it when one is double clicked
+fn launch_file() -> Option<PathBuf>
+{
+    std::env::args_os()
+        .nth(1)
+        .map(PathBuf::from)
+        .filter(|path| path.is_file())
+}
+
+/// a file dialog starting in `folder`, or wherever the system picks without one
+fn file_dialog(folder: Option<&std::path::Path>) -> rfd::FileDialog
+{
+    match folder
+    {
+        Some(folder) => rfd::FileDialog::new().set_directory(folder),
+        None => rfd::FileDialog::new()
+    }
+}
+
+/// the uri the image loaders know the spritesheet at `path` by
+fn sheet_uri(path: &std::path::Path) -> String
+{
+    "file://".to_owned() + path
+        .to_str()
+        .expect
+        (
+            "received invalid UTF-8, why not just use ostr as source anyway?"
+        )
+}
+
+/// where images of the spritesheet at `path` come from, its uri unless it had to be decoded by hand
+fn sheet_source(path: &std::path::Path, fallback: &Option<FallbackTexture>) -> ui::ImageSource<'static>
+{
+    match fallback
+    {
+        Some((fallback_path, .., Some(texture))) if fallback_path == path => ui::load::SizedTexture::from_handle(texture).into(),
+        _ => sheet_uri(path).into()
+    }
+}
+
+/// returns the index of the tile with `uv`, `None` if it isn't one of the tiles
+fn tile_index(uv: ui::Rect, rows: usize, columns: usize) -> Option<usize>
+{
+    let row = (uv.min.x * rows as f32).round() as usize;
+    let column = (uv.min.y * columns as f32).round() as usize;
+
+    let idx = column * rows + row;
+
+    (row < rows && column < columns && tile_uv(idx, rows, columns) == uv).then_some(idx)
+}
+
+/// returns the uv of the tile at `idx`, counting row by row
+fn tile_uv(idx: usize, rows: usize, columns: usize) -> ui::Rect
+{
+    let (row, column) = (idx % rows, idx / rows);
+
+    let vmax = 0. + (1. / columns as f32) * (column + 1) as f32;
+    let vmin = 0. + (1. / columns as f32) * column as f32;
+
+    let umax = 0. + (1. / rows as f32) * (row + 1) as f32;
+    let umin = 0. + (1. / rows as f32) * row as f32;
+
+    ui::Rect::from_min_max(ui::pos2(umin, vmin), ui::pos2(umax, vmax))
+}
+
+/// returns the divisor of `length` closest to `count`
+fn nearest_divisor(length: usize, count: usize) -> usize
+{
+    (1..=length.max(1))
+        .filter(|divisor| length.is_multiple_of(*divisor))
+        .min_by_key(|divisor| divisor.abs_diff(count))
+        .unwrap_or(1)
+}
+
+/// puts a grid line on every cell edge, skipping some evenly once they would get too dense to see
+fn tile_grid_spacer(input: plot::GridInput) -> Vec<plot::GridMark>
+{
+    let step = input.base_step_size.ceil().max(1.);
+    let (min, max) = input.bounds;
+
+    let first = (min / step).floor() as i64;
+    let last = (max / step).ceil() as i64;
+
+    (first..=last)
+        .map(|mark| plot::GridMark { value: mark as f64 * step, step_size: step })
+        .collect()
+}
+
+/// labels the grid lines that fall on a cell edge with the cell index moved by `offset`,
+/// the others stay blank
+fn cell_coordinate(offset: i32) -> impl Fn(plot::GridMark, usize, &std::ops::RangeInclusive<f64>) -> String
+{
+    move |mark, _, _| if mark.value.fract() == 0.
+    {
+        format!("{}", mark.value as i64 + offset as i64)
+    }
+    else
+    {
+        String::new()
+    }
+}
+
+/// past this many cells a shape is previewed by its bounds alone
+const MAX_SHAPE_PREVIEW_CELLS: usize = 4096;
+
+/// past this many blocks across the view the super-grid isn't drawn
+const MAX_SUPER_GRID_LINES: f64 = 128.;
+
+/// how tall each pane of the split palette is before it scrolls
+const PALETTE_PANE_HEIGHT: f32 = 260.;
+
+/// what the palette images are keyed on before any was built, matching no real page
+const EMPTY_PALETTE_KEY: (usize, usize, ui::TextureOptions, Vec<usize>) = (0, 0, ui::TextureOptions::NEAREST, Vec::new());
+
+/// the color key offered first, the magenta most old tilesets use
+const DEFAULT_COLOR_KEY: [u8; 3] = [255, 0, 255];
+
+/// how close to the edge of the canvas, in pixels, a drag starts scrolling the view
+const AUTO_SCROLL_MARGIN: f32 = 40.;
+
+/// how many pixels a second the view scrolls with the cursor right on the edge
+const AUTO_SCROLL_SPEED: f32 = 600.;
+
+/// how many cells away from placed tiles the magnetic brush still gets pulled
+const MAGNET_RADIUS: i32 = 2;
+
+/// the block of the super-grid, `size` cells wide, that `cell` falls in
+fn super_grid_block(cell: TilePos, size: i32) -> Selection
+{
+    let start = TilePos { x: cell.x.div_euclid(size) * size, y: cell.y.div_euclid(size) * size };
 
-            // here we will gather the tiles we are replacing with the redo tiles,
-            // so that we can use them as undo operation later
-            let mut undo_tiles = IndexMap::with_capacity(redo_tiles.len());
+    Selection { start, end: TilePos { x: start.x + size - 1, y: start.y + size - 1 } }
+}
 
-            for (pos, uv) in redo_tiles
-            {
-                if uv == ui::Rect::NOTHING
-                {
-                    match self.tiles.remove(&pos)
-                    {
-                        Some(old_uv) => undo_tiles.insert(pos, old_uv),
-                        None => undo_tiles.insert(pos, ui::Rect::NOTHING)
-                    }
-                }
-                else
-                {
-                    match self.tiles.insert(pos, uv)
-                    {
-                        Some(old_uv) => undo_tiles.insert(pos, old_uv),
-                        None => undo_tiles.insert(pos, ui::Rect::NOTHING)
-                    }
-                };
-            }
+/// the cells a stroke paints around `cell`, a square `size` cells wide leaning up and right when its size is even,
+/// or the whole block of the super-grid while snapping to one `super_grid` cells wide
+fn brush_cells(cell: TilePos, size: i32, super_grid: Option<i32>) -> Vec<TilePos>
+{
+    match super_grid
+    {
+        Some(super_grid) => super_grid_block(cell, super_grid).cells().collect(),
+        None => ((1 - size) / 2..=size / 2)
+            .flat_map(move |y| ((1 - size) / 2..=size / 2).map(move |x| TilePos { x: cell.x + x, y: cell.y + y }))
+            .collect()
+    }
+}
 
-            self.undos.add(undo_tiles)
-        }
+/// returns the empty cell touching a placed tile that is closest to `cell`,
+/// or `cell` itself if it's already touching one or nothing is close enough
+fn magnet_cell(tiles: &Tiles, cell: TilePos) -> TilePos
+{
+    let touches_tile = |pos: TilePos| [(1, 0), (-1, 0), (0, 1), (0, -1)]
+        .iter()
+        .any(|(x, y)| tiles.contains_key(&TilePos { x: pos.x + x, y: pos.y + y }));
 
-        if app.input.get_key_down(input::KeyCode::KeyS)
-            && app.input.get_key_holding(input::KeyCode::ControlLeft)
-        {
-            let _ = self.save_workspace();
-        }
+    if tiles.contains_key(&cell) || touches_tile(cell)
+    {
+        return cell
     }
 
-    fn save_workspace(&mut self) -> bincode::Result<()>
+    (-MAGNET_RADIUS..=MAGNET_RADIUS)
+        .flat_map(|y| (-MAGNET_RADIUS..=MAGNET_RADIUS).map(move |x| TilePos { x: cell.x + x, y: cell.y + y }))
+        .filter(|pos| !tiles.contains_key(pos) && touches_tile(*pos))
+        .min_by_key(|pos| (pos.x - cell.x).pow(2) + (pos.y - cell.y).pow(2))
+        .unwrap_or(cell)
+}
+
+/// returns every cell on the line going from `from` to `to`, both included
+fn line_cells(from: TilePos, to: TilePos) -> Vec<TilePos>
+{
+    let (dx, dy) = ((to.x - from.x).abs(), -(to.y - from.y).abs());
+    let (step_x, step_y) = ((to.x - from.x).signum(), (to.y - from.y).signum());
+
+    let mut cells = Vec::with_capacity(dx.max(-dy) as usize + 1);
+
+    let mut cell = from;
+    let mut error = dx + dy;
+
+    loop
     {
-        let sprite_sheet = match self.sprite_sheet
+        cells.push(cell);
+
+        if cell == to
         {
-            Some(ref sprite_sheet) => sprite_sheet.clone(),
-            None =>
-            {
-                return bincode::Result::Err
-                (
-                    Box::new(bincode::ErrorKind::Custom("no spritesheet chosen yre".to_owned()))
-                )
-            }
-        };
+            return cells
+        }
 
-        // create the savedata struct
-        let mut data = SavedData { sprite_sheet, tiles: Vec::new() };
+        let double_error = error * 2;
 
-        // here we pass the tiles we have drawn to the vec
-        for tile in &self.tiles
+        if double_error >= dy
         {
-            data.tiles.push((*tile.0, *tile.1))
+            error += dy;
+            cell.x += step_x
         }
 
-        if self.workspace_path.is_none()
+        if double_error <= dx
         {
-            match rfd::FileDialog::new()
-                    .add_filter("", &["bag"])
-                    .set_file_name("new tilemap.bag")
-                    .save_file()
-            {
-                Some(path) =>
-                {
-                    self.workspace_path = Some(path)
-                },
-                None => return bincode::Result::Err
-                (
-                    Box::new(bincode::ErrorKind::Custom("failed to crate save file".to_owned()))
-                )
-            }
+            error += dx;
+            cell.y += step_y
         }
-        
-        let path = self.workspace_path.as_ref().expect
-        (
-            "this call should have been unreachable as none"
-        );
-
-        let mut file = std::fs::File::create(path)?;
-        let data = bincode::serialize(&data)?;
-        file.write_all(&data)?;
-    
-        Ok(())
     }
-    
-    fn load_workspace(&mut self) -> bincode::Result<()>
+}
+
+/// returns the cells connected to `start` holding the same tile as it, or nothing like it,
+/// or any tile of `group` if it holds one of them,
+/// without going outside of `bounds` so an empty start can't spread forever
+fn flood_cells(tiles: &Tiles, start: TilePos, bounds: Selection, group: &[ui::Rect]) -> Vec<TilePos>
+{
+    let target = tiles.get(&start);
+
+    // a start on a tile of the group spreads over every tile of it, any other start only over its own tile
+    let spreads_over = |uv: Option<&ui::Rect>| match target.filter(|target| group.contains(target))
     {
-        let Some(worskspace_path) = rfd::FileDialog::new()
-            .add_filter("", &["bag"])
-            .set_file_name("load spritesheet data")
-            .pick_file()
-        else
-        {
-            return bincode::Result::Err
-            (
-                Box::new(bincode::ErrorKind::Custom("invalid path".to_owned()))
-            )
-        };
+        Some(_) => uv.is_some_and(|uv| group.contains(uv)),
+        None => uv == target
+    };
 
-        let mut file = std::fs::File::open(&worskspace_path)?;
+    let mut cells = Vec::new();
+    let mut reached = HashSet::from([start]);
+    let mut pending = vec![start];
 
-        let mut buf = Vec::new();
+    while let Some(pos) = pending.pop()
+    {
+        cells.push(pos);
 
-        file.read_to_end(&mut buf)?;
-    
-        match bincode::deserialize::<SavedData>(&buf)
+        for (x, y) in [(1, 0), (-1, 0), (0, 1), (0, -1)]
         {
-            Ok(SavedData { sprite_sheet, tiles }) =>
-            {
-                self.tiles.clear();
-                self.undos.clear();
-                self.redos.clear();
+            let next = TilePos { x: pos.x + x, y: pos.y + y };
 
-                self.sprite_sheet = Some(sprite_sheet);
-                self.workspace_path = Some(worskspace_path);
-
-                for (pos,uv) in tiles
-                {
-                    self.tiles.insert(pos, uv);
-                }
-                
-                Ok(())
+            if bounds.contains(next) && spreads_over(tiles.get(&next)) && reached.insert(next)
+            {
+                pending.push(next)
             }
-            Err(err) => Err(err)
         }
     }
+
+    cells
 }
 
-fn load_images<'a>
-(
-    uri: impl Into<std::borrow::Cow<'a, str>>,
-    rows: usize,
-    columns: usize
-) -> impl Iterator<Item = ui::Image<'a>>
+/// the tiles a history step writes, without the opacity the operation log doesn't keep
+fn written(delta: &[(TilePos, ui::Rect, u8)]) -> Vec<(TilePos, ui::Rect)>
 {
-    let mut items = Vec::with_capacity(rows * columns);
-    
-    let image = ui::Image::from_uri(uri);
+    delta.iter().map(|(pos, uv, _)| (*pos, *uv)).collect()
+}
 
-    for column in 0..columns
+/// writes `bytes` in the largest unit that keeps it above one
+fn format_size(bytes: u64) -> String
+{
+    match bytes
     {
-        let vmax = 0. + (1. / columns as f32) * (column + 1) as f32;
-        let vmin = 0. + (1. / columns as f32) * column as f32;
+        0..=1023 => format!("{bytes} B"),
+        1024..=1_048_575 => format!("{:.1} KiB", bytes as f64 / 1024.),
+        _ => format!("{:.1} MiB", bytes as f64 / 1_048_576.)
+    }
+}
 
-        for row in 0..rows
-        {
-            let umax = 0. + (1. / rows as f32) * (row + 1) as f32;
-            let umin = 0. + (1. / rows as f32) * row as f32;
+type Tiles = IndexMap<TilePos,ui::Rect>;
 
-            items.push
-            (
-                image
-                    .clone()
-                    .texture_options(ui::TextureOptions::NEAREST)
-                    .uv([ui::pos2(umin, vmin), ui::pos2(umax , vmax)])
-            )
-        };
-    }
+/// a spritesheet decoded by hand along with the path, filtering and color key it was decoded with
+type FallbackTexture = (PathBuf, ui::TextureOptions, Option<[u8; 3]>, Option<ui::TextureHandle>);
 
-    items.into_iter()
+/// a rectangle of cells, both corners included
+#[derive(Clone, Copy)]
+struct Selection
+{
+    /// the cell the drag started from
+    start: TilePos,
+    /// the cell the drag ended on
+    end: TilePos
 }
 
-type Tiles = IndexMap<TilePos,ui::Rect>;
+impl Selection
+{
+    fn min(&self) -> TilePos
+    {
+        TilePos { x: self.start.x.min(self.end.x), y: self.start.y.min(self.end.y) }
+    }
+
+    fn max(&self) -> TilePos
+    {
+        TilePos { x: self.start.x.max(self.end.x), y: self.start.y.max(self.end.y) }
+    }
+
+    /// the corner copied tiles are relative to
+    fn top_left(&self) -> TilePos
+    {
+        TilePos { x: self.min().x, y: self.max().y }
+    }
+
+    fn contains(&self, pos: TilePos) -> bool
+    {
+        let (min, max) = (self.min(), self.max());
+
+        (min.x..=max.x).contains(&pos.x) && (min.y..=max.y).contains(&pos.y)
+    }
+
+    fn cells(&self) -> impl Iterator<Item = TilePos>
+    {
+        let (min, max) = (self.min(), self.max());
+
+        (min.y..=max.y).flat_map(move |y| (min.x..=max.x).map(move |x| TilePos { x, y }))
+    }
+}
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
 #[cfg_attr(debug_assertions, derive(Debug))]
@@ -611,38 +6632,137 @@ struct TilePos
     x: i32, y: i32
 }
 
-struct TilesHistory(std::collections::VecDeque<Tiles>, u16);
+/// the values some cells had before an edit along with their opacity, where nothing means the cell was empty
+type TilesDelta = Box<[(TilePos, ui::Rect, u8)]>;
+
+/// how often the spritesheet file is looked at for changes made outside of the editor
+const SHEET_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// how long the scale bar in the corner of the canvas can be, in points
+const SCALE_BAR_POINTS: std::ops::RangeInclusive<f64> = 40. ..=120.;
+
+/// how many of the most used tiles the stats list
+const STATS_USAGE_ROWS: usize = 8;
+
+/// the most backups a workspace keeps of its earlier saves
+const MAX_BACKUPS: usize = 32;
+
+/// the colors the cells added, removed and changed since the compared map are drawn with
+const DIFF_ADDED: ui::Color32 = ui::Color32::from_rgb(90, 200, 110);
+const DIFF_REMOVED: ui::Color32 = ui::Color32::from_rgb(235, 90, 90);
+const DIFF_CHANGED: ui::Color32 = ui::Color32::from_rgb(240, 190, 60);
+
+/// how long a toast stays in the corner
+const TOAST_DURATION: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// how many cells from the middle of the map a tile has to be to count as stray
+const STRAY_DISTANCE: u32 = 1000;
+
+/// how long the cells changed by an undo or redo stay highlighted
+const HISTORY_FLASH_SECONDS: f32 = 0.3;
+
+/// how many tiles are remembered for cycling with the scroll wheel
+const RECENT_TILES: usize = 8;
+
+/// the most cells a history keeps across all of its entries unless told otherwise
+const HISTORY_CELL_BUDGET: usize = 4_000_000;
+
+/// a stack of deltas where each entry undoes the one after it,
+/// the back is the next step to take and the front the furthest one
+///
+/// undos and redos together form a single chain of states going through the current one,
+/// truncating only ever drops from the front, which is the far end of that chain,
+/// so what's left always connects to the current state and stepping past it just stops
+struct TilesHistory
+{
+    entries: std::collections::VecDeque<TilesDelta>,
+    /// how many entries are kept at most
+    depth: u16,
+    /// the most cells kept across all the entries,
+    /// past this the oldest entries are dropped even if the depth would allow them
+    budget: usize,
+    /// the amount of cells stored across all the entries
+    cells: usize
+}
 
 impl TilesHistory
 {
-    fn new() -> Self
+    fn new(depth: u16) -> Self
+    {
+        Self { entries: Default::default(), depth, budget: HISTORY_CELL_BUDGET, cells: 0 }
+    }
+
+    /// changes how many operations are kept, dropping the oldest ones if needed
+    fn set_depth(&mut self, depth: u16)
     {
-        Self(Default::default(), 5)
+        self.depth = depth;
+        self.truncate()
     }
 
     /// add an undo operation
-    fn add(&mut self, tiles: IndexMap<TilePos, ui::Rect>)
+    fn add(&mut self, tiles: impl IntoIterator<Item = (TilePos, ui::Rect, u8)>)
+    {
+        // a slice is much smaller than a map, big fills would weight a lot otherwise
+        let delta = tiles.into_iter().collect::<TilesDelta>();
+
+        self.cells += delta.len();
+        self.entries.push_back(delta);
+
+        self.truncate()
+    }
+
+    /// drops the furthest entries until both the depth and the cell budget are respected,
+    /// the newest entry is always kept so even a huge edit can be undone
+    fn truncate(&mut self)
     {
-        if self.0.len() >= self.1 as usize
+        while self.entries.len() > self.depth as usize
+            || (self.cells > self.budget && self.entries.len() > 1)
         {
-            self.0.pop_front();
+            let Some(dropped) = self.entries.pop_front() else
+            {
+                break
+            };
+
+            self.cells -= dropped.len()
         }
+    }
+
+    fn len(&self) -> usize
+    {
+        self.entries.len()
+    }
 
-        self.0.push_back(tiles)
+    fn is_empty(&self) -> bool
+    {
+        self.entries.is_empty()
     }
 
     /// returns the last values added or `None` if the queue has been emptied
-    fn pop(&mut self) -> Option<IndexMap<TilePos, ui::Rect>>
+    fn pop(&mut self) -> Option<TilesDelta>
     {
-        self.0.pop_back()
+        let delta = self.entries.pop_back()?;
+        self.cells -= delta.len();
+
+        Some(delta)
     }
 
     fn clear(&mut self)
     {
-        self.0.clear()
+        self.entries.clear();
+        self.cells = 0
     }
 }
 
+/// the bincode configuration of `.bag` files, spelled out so they read the same on every machine,
+/// it matches what `bincode::serialize` has always written so older files still load
+fn save_format() -> impl bincode::Options
+{
+    bincode::DefaultOptions::new()
+        .with_fixint_encoding()
+        .with_little_endian()
+        .allow_trailing_bytes()
+}
+
 /// contains a path to the spritesheet image, 
 /// and the tile data 
 #[derive(Serialize,Deserialize)]
@@ -651,4 +6771,222 @@ struct SavedData
     /// the path to the spritesheet used
     sprite_sheet: SpriteSheet,
     tiles: Vec<(TilePos,ui::Rect)>
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    /// a delta of `cells` copies of the same cell, the history only counts them
+    fn delta(x: i32, cells: usize) -> impl Iterator<Item = (TilePos, ui::Rect, u8)>
+    {
+        std::iter::repeat((TilePos { x, y: 0 }, tile_uv(0, 2, 2), u8::MAX)).take(cells)
+    }
+
+    /// the x of the cells each entry holds, the furthest first
+    fn entries(history: &TilesHistory) -> Vec<i32>
+    {
+        history.entries.iter().map(|delta| delta[0].0.x).collect()
+    }
+
+    #[test]
+    fn history_keeps_the_newest_entries_up_to_its_depth()
+    {
+        let mut history = TilesHistory::new(3);
+
+        for x in 0..5
+        {
+            history.add(delta(x, 1))
+        }
+
+        assert_eq!(entries(&history), [2, 3, 4]);
+        assert_eq!(history.cells, 3);
+
+        history.set_depth(1);
+
+        assert_eq!(entries(&history), [4]);
+        assert_eq!(history.cells, 1);
+    }
+
+    const BUDGET: usize = 8;
+
+    /// a history limited by a budget of `BUDGET` cells rather than by its depth
+    fn budgeted() -> TilesHistory
+    {
+        TilesHistory { budget: BUDGET, ..TilesHistory::new(u16::MAX) }
+    }
+
+    #[test]
+    fn history_drops_the_oldest_entries_past_the_cell_budget()
+    {
+        let mut history = budgeted();
+
+        // right at the budget everything is kept
+        history.add(delta(0, 1));
+        history.add(delta(1, BUDGET - 1));
+
+        assert_eq!(entries(&history), [0, 1]);
+        assert_eq!(history.cells, BUDGET);
+
+        // a single cell over it and the furthest entry goes
+        history.add(delta(2, 1));
+
+        assert_eq!(entries(&history), [1, 2]);
+        assert_eq!(history.cells, BUDGET);
+    }
+
+    #[test]
+    fn history_always_keeps_the_newest_entry()
+    {
+        let mut history = budgeted();
+
+        history.add(delta(0, 1));
+        history.add(delta(1, BUDGET + 1));
+
+        assert_eq!(entries(&history), [1]);
+        assert_eq!(history.cells, BUDGET + 1);
+
+        let popped = history.pop().expect("the newest entry is kept");
+
+        assert_eq!(popped.len(), BUDGET + 1);
+        assert_eq!(history.cells, 0);
+        assert!(history.is_empty());
+    }
+
+    #[test]
+    fn undo_and_redo_still_link_up_after_truncating()
+    {
+        let settings = settings::ProjectSettings { history_depth: 2, ..Default::default() };
+        let mut workspace = Workspace::with_settings(settings);
+
+        let uv = tile_uv(1, 2, 2);
+        let cells = (0..4).map(|x| TilePos { x, y: 0 }).collect::<Vec<_>>();
+
+        for pos in &cells
+        {
+            workspace.edit(Tiles::from([(*pos, uv)]), oplog::Operation::stroke([*pos], uv))
+        }
+
+        assert_eq!(workspace.undos.len(), 2);
+
+        // the two oldest edits can't be undone anymore, stepping past the last one kept just stops
+        for _ in 0..4
+        {
+            workspace.undo()
+        }
+
+        assert!(workspace.tiles.keys().eq(&cells[..2]));
+        assert_eq!(workspace.redos.len(), 2);
+
+        for _ in 0..4
+        {
+            workspace.redo()
+        }
+
+        assert_eq!(workspace.tiles.len(), cells.len());
+        assert!(cells.iter().all(|pos| workspace.tiles.get(pos) == Some(&uv)));
+        assert_eq!(workspace.undos.len(), 2);
+        assert!(workspace.redos.is_empty());
+    }
+
+    #[test]
+    fn repainting_identical_tiles_leaves_the_history_alone()
+    {
+        let mut workspace = Workspace::new();
+
+        let uv = tile_uv(2, 2, 2);
+        let painted = (0..3).map(|x| (TilePos { x, y: 1 }, uv)).collect::<Tiles>();
+
+        let paint = |workspace: &mut Workspace, alpha|
+        {
+            let operation = oplog::Operation::stroke(painted.keys().copied(), uv);
+            workspace.edit_with_opacity(painted.clone(), alpha, operation)
+        };
+
+        paint(&mut workspace, None);
+
+        assert_eq!(workspace.undos.len(), 1);
+        assert_eq!(workspace.operations.len(), 1);
+
+        paint(&mut workspace, None);
+        paint(&mut workspace, Some(u8::MAX));
+
+        assert_eq!(workspace.undos.len(), 1);
+        assert_eq!(workspace.operations.len(), 1);
+        assert!(workspace.redos.is_empty());
+
+        // the same tiles drawn with another opacity are an edit of their own
+        paint(&mut workspace, Some(128));
+
+        assert_eq!(workspace.undos.len(), 2);
+
+        workspace.undo();
+
+        assert!(painted.keys().all(|pos| workspace.opacity_at(*pos) == u8::MAX));
+        assert!(workspace.tiles == painted);
+    }
+
+    #[test]
+    fn only_new_tiles_go_through_the_overflow_policy()
+    {
+        let settings = settings::ProjectSettings
+        {
+            max_coordinate: 10,
+            overflow: settings::OverflowPolicy::Reject,
+            ..Default::default()
+        };
+
+        let mut workspace = Workspace::with_settings(settings);
+        workspace.sprite_sheet = Some(SpriteSheet { path: PathBuf::from("sheet.png"), rows: 4, columns: 2 });
+
+        // the stray was placed before the limit was lowered
+        let (near, stray) = (TilePos { x: 1, y: 1 }, TilePos { x: 50, y: 0 });
+        workspace.tiles.insert(near, tile_uv(3, 2, 2));
+        workspace.tiles.insert(stray, tile_uv(2, 2, 2));
+
+        workspace.remap_tiles((2, 2));
+
+        assert!(workspace.tiles.get(&near) == Some(&tile_uv(3, 4, 2)));
+        assert!(workspace.tiles.get(&stray) == Some(&tile_uv(2, 4, 2)));
+        assert!(workspace.undos.is_empty());
+
+        // while a fill reaching past the limit is still refused whole
+        workspace.fill(Selection { start: TilePos { x: 9, y: 0 }, end: TilePos { x: 11, y: 0 } }, tile_uv(0, 4, 2));
+
+        assert_eq!(workspace.tiles.len(), 2);
+    }
+
+    #[test]
+    fn replaying_the_log_places_what_the_edits_placed()
+    {
+        let settings = settings::ProjectSettings
+        {
+            max_coordinate: 10,
+            overflow: settings::OverflowPolicy::Clip,
+            ..Default::default()
+        };
+
+        let mut workspace = Workspace::with_settings(settings);
+        workspace.sprite_sheet = Some(SpriteSheet { path: PathBuf::from("sheet.png"), rows: 2, columns: 2 });
+
+        // only the cells inside the limit are placed, and only they get logged
+        workspace.fill(Selection { start: TilePos { x: 9, y: 0 }, end: TilePos { x: 11, y: 0 } }, tile_uv(1, 2, 2));
+
+        // a tile far past the default limit, placed once the limit was raised
+        workspace.settings.overflow = settings::OverflowPolicy::Expand;
+        workspace.fill(Selection { start: TilePos { x: 500_000, y: 0 }, end: TilePos { x: 500_000, y: 0 } }, tile_uv(2, 2, 2));
+
+        let path = std::env::temp_dir().join(format!("baguette-oplog-{}.json", std::process::id()));
+        let exported = oplog::export(&path, &workspace);
+        let replayed = oplog::replay(&path);
+
+        let _ = std::fs::remove_file(&path);
+
+        exported.expect("the log is written");
+        let replayed = replayed.expect("the log is replayed");
+
+        assert_eq!(workspace.tiles.len(), 3);
+        assert!(replayed.tiles == workspace.tiles);
+    }
+}
@@ -0,0 +1,57 @@
+use baguette::app::ui;
+use serde::{Deserialize, Serialize};
+
+/// how far from a point object a click still picks it, in cells
+const POINT_PICK_RADIUS: f64 = 0.3;
+
+/// a marker placed anywhere on the map, independent of the tile grid
+#[derive(Clone, PartialEq)]
+#[derive(Serialize, Deserialize)]
+pub struct MapObject
+{
+    /// what the object stands for, like a spawn point or a trigger
+    pub kind: String,
+    /// where a point sits, or the bottom left corner of an area, in cells
+    pub x: f64,
+    pub y: f64,
+    /// the width and height of an area, nothing for a point
+    pub size: Option<(f64, f64)>,
+    pub properties: Vec<(String, String)>
+}
+
+impl MapObject
+{
+    pub fn point(kind: String, x: f64, y: f64) -> Self
+    {
+        Self { kind, x, y, size: None, properties: Vec::new() }
+    }
+
+    /// an area spanning the rectangle between two opposite corners
+    pub fn area(kind: String, from: [f64; 2], to: [f64; 2]) -> Self
+    {
+        let (x, y) = (from[0].min(to[0]), from[1].min(to[1]));
+        let size = ((from[0] - to[0]).abs(), (from[1] - to[1]).abs());
+
+        Self { kind, x, y, size: Some(size), properties: Vec::new() }
+    }
+
+    /// every object of the same kind gets the same color, so they're told apart at a glance
+    pub fn color(&self) -> ui::Color32
+    {
+        let hash = self.kind.bytes().fold(0u32, |hash, byte| hash.wrapping_mul(31).wrapping_add(byte as u32));
+        let hue = (hash % 360) as f32 / 360.;
+
+        ui::ecolor::Hsva::new(hue, 0.65, 0.95, 1.).into()
+    }
+
+    /// tells if a click at `point` lands on this object
+    pub fn contains(&self, point: [f64; 2]) -> bool
+    {
+        match self.size
+        {
+            Some((width, height)) => (self.x..=self.x + width).contains(&point[0])
+                && (self.y..=self.y + height).contains(&point[1]),
+            None => (point[0] - self.x).hypot(point[1] - self.y) <= POINT_PICK_RADIUS
+        }
+    }
+}
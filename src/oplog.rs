@@ -0,0 +1,175 @@
+use std::path::Path;
+
+use baguette::app::ui;
+use serde::{Deserialize, Serialize};
+
+use crate::{Selection, SpriteSheet, TilePos, Tiles, Workspace};
+
+/// tells our logs apart from any other json
+const FORMAT: &str = "baguette operation log";
+
+/// bumped whenever an operation changes shape
+const LOG_VERSION: u32 = 1;
+
+/// a single edit made to a map, enough to make it again on another one
+///
+/// erased cells are listed by position, since an empty uv can't go through json
+#[derive(Clone)]
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum Operation
+{
+    Paint { tiles: Vec<(TilePos, ui::Rect)> },
+    Erase { cells: Vec<TilePos> },
+    Fill { min: TilePos, max: TilePos, uv: ui::Rect },
+    /// the whole map got swapped, like when importing or loading
+    Replace { tiles: Vec<(TilePos, ui::Rect)> },
+    /// some tiles moved over by a few cells
+    Nudge { painted: Vec<(TilePos, ui::Rect)>, erased: Vec<TilePos> },
+    Undo { painted: Vec<(TilePos, ui::Rect)>, erased: Vec<TilePos> },
+    Redo { painted: Vec<(TilePos, ui::Rect)>, erased: Vec<TilePos> }
+}
+
+impl Operation
+{
+    /// paints every cell with the same `uv`, or erases them if it's nothing
+    pub fn stroke(cells: impl IntoIterator<Item = TilePos>, uv: ui::Rect) -> Self
+    {
+        if uv == ui::Rect::NOTHING
+        {
+            Operation::Erase { cells: cells.into_iter().collect() }
+        }
+        else
+        {
+            Operation::Paint { tiles: cells.into_iter().map(|pos| (pos, uv)).collect() }
+        }
+    }
+
+    pub fn fill(area: Selection, uv: ui::Rect) -> Self
+    {
+        Operation::Fill { min: area.min(), max: area.max(), uv }
+    }
+
+    /// the operation as it was made once the overflow policy left out the cells past the coordinate limit,
+    /// `kept` being the cells it still wrote, so replaying it leaves out the same ones
+    pub fn clipped(self, kept: &Tiles) -> Self
+    {
+        match self
+        {
+            Operation::Paint { tiles } => Operation::Paint
+            {
+                tiles: tiles.into_iter().filter(|(pos, _)| kept.contains_key(pos)).collect()
+            },
+            // a clipped fill isn't a rectangle anymore, and only fills placing a tile get clipped
+            Operation::Fill { .. } => Operation::Paint
+            {
+                tiles: kept.iter().map(|(pos, uv)| (*pos, *uv)).collect()
+            },
+            operation => operation
+        }
+    }
+
+    /// splits the values written by a nudge, an undo or a redo into the painted and erased cells
+    fn split(tiles: &[(TilePos, ui::Rect)]) -> (Vec<(TilePos, ui::Rect)>, Vec<TilePos>)
+    {
+        let (erased, painted): (Vec<_>, Vec<_>) = tiles
+            .iter()
+            .partition(|(_, uv)| *uv == ui::Rect::NOTHING);
+
+        (painted, erased.into_iter().map(|(pos, _)| pos).collect())
+    }
+
+    pub fn nudge(written: &[(TilePos, ui::Rect)]) -> Self
+    {
+        let (painted, erased) = Self::split(written);
+        Operation::Nudge { painted, erased }
+    }
+
+    pub fn undo(written: &[(TilePos, ui::Rect)]) -> Self
+    {
+        let (painted, erased) = Self::split(written);
+        Operation::Undo { painted, erased }
+    }
+
+    pub fn redo(written: &[(TilePos, ui::Rect)]) -> Self
+    {
+        let (painted, erased) = Self::split(written);
+        Operation::Redo { painted, erased }
+    }
+
+    /// the values this operation writes, where nothing means the cell gets emptied
+    fn changes(&self) -> Tiles
+    {
+        match self
+        {
+            Operation::Paint { tiles } | Operation::Replace { tiles } => tiles.iter().copied().collect(),
+            Operation::Erase { cells } => cells.iter().map(|pos| (*pos, ui::Rect::NOTHING)).collect(),
+            Operation::Fill { min, max, uv } => (min.y..=max.y)
+                .flat_map(|y| (min.x..=max.x).map(move |x| (TilePos { x, y }, *uv)))
+                .collect(),
+            Operation::Nudge { painted, erased }
+            | Operation::Undo { painted, erased }
+            | Operation::Redo { painted, erased } => painted
+                .iter()
+                .copied()
+                .chain(erased.iter().map(|pos| (*pos, ui::Rect::NOTHING)))
+                .collect()
+        }
+    }
+}
+
+/// the edits of a workspace in the order they were made
+#[derive(Serialize, Deserialize)]
+struct OperationLog
+{
+    format: String,
+    version: u32,
+
+    sprite_sheet: Option<SpriteSheet>,
+    operations: Vec<Operation>
+}
+
+/// writes the operations of `workspace` as json at `path`
+pub fn export(path: &Path, workspace: &Workspace) -> std::io::Result<()>
+{
+    let log = OperationLog
+    {
+        format: FORMAT.to_owned(),
+        version: LOG_VERSION,
+
+        sprite_sheet: workspace.sprite_sheet.clone(),
+        operations: workspace.operations.clone()
+    };
+
+    std::fs::write(path, serde_json::to_string_pretty(&log)?)
+}
+
+/// builds a new workspace by making every operation logged at `path` again,
+/// each one ends up as its own undoable edit
+///
+/// the log only holds what the overflow policy let through, so the coordinate limit isn't checked again,
+/// tiles placed past the default limit after it was raised come back too
+pub fn replay(path: &Path) -> std::io::Result<Workspace>
+{
+    let log = serde_json::from_str::<OperationLog>(&std::fs::read_to_string(path)?)?;
+
+    if log.format != FORMAT || log.version > LOG_VERSION
+    {
+        let message = "not an operation log this version can read";
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, message))
+    }
+
+    let mut workspace = Workspace::new();
+    workspace.sprite_sheet = log.sprite_sheet;
+
+    for operation in log.operations
+    {
+        match operation
+        {
+            Operation::Replace { .. } => workspace.replace_tiles(operation.changes()),
+            _ => workspace.edit(operation.changes(), operation)
+        }
+    }
+
+    Ok(workspace)
+}
@@ -0,0 +1,108 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Anchor, BrushShape, PaintMode, SecondaryButton, Tool};
+
+/// how the editor was set up when it was last closed, shared by every workspace
+/// unlike the project settings
+#[derive(Clone, PartialEq)]
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub struct Preferences
+{
+    pub tool: Tool,
+    pub brush_size: i32,
+    pub brush_density: f32,
+    pub brush_shape: BrushShape,
+    pub hollow_shapes: bool,
+    pub shape_border: i32,
+    pub paint_mode: PaintMode,
+    pub magnetic_placement: bool,
+    pub snap_to_super_grid: bool,
+    pub super_grid_size: i32,
+    pub stamp_anchor: Anchor,
+    pub secondary_button: SecondaryButton,
+    /// reloads the spritesheet as soon as another program saves it
+    pub auto_reload_sheet: bool,
+
+    /// where the last spritesheet was picked from, the file dialogs for sheets open there
+    pub sheet_folder: Option<PathBuf>,
+    /// where the last workspace was saved or opened, the file dialogs for workspaces open there
+    pub workspace_folder: Option<PathBuf>,
+
+    /// the spritesheet of the last selected tile and its index,
+    /// selected again when a workspace using that same sheet is opened
+    pub last_tile: Option<(PathBuf, usize)>
+}
+
+impl Default for Preferences
+{
+    fn default() -> Self
+    {
+        Self
+        {
+            tool: Tool::Paint,
+            brush_size: 1,
+            brush_density: 1.,
+            brush_shape: BrushShape::Freehand,
+            hollow_shapes: false,
+            shape_border: 1,
+            paint_mode: PaintMode::Replace,
+            magnetic_placement: false,
+            snap_to_super_grid: false,
+            super_grid_size: 4,
+            stamp_anchor: Anchor::TopLeft,
+            secondary_button: SecondaryButton::Erase,
+            auto_reload_sheet: true,
+
+            sheet_folder: None,
+            workspace_folder: None,
+
+            last_tile: None
+        }
+    }
+}
+
+impl Preferences
+{
+    /// reads the preferences of the last session, falls back to the defaults if there are none
+    pub fn load() -> Self
+    {
+        preferences_path()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> std::io::Result<()>
+    {
+        let Some(path) = preferences_path() else
+        {
+            return Ok(())
+        };
+
+        if let Some(folder) = path.parent()
+        {
+            std::fs::create_dir_all(folder)?
+        }
+
+        std::fs::write(path, serde_json::to_string_pretty(self)?)
+    }
+}
+
+/// the folder of the editor inside the config folder of the current user
+pub fn config_folder() -> Option<PathBuf>
+{
+    let config = std::env::var_os("APPDATA")
+        .or_else(|| std::env::var_os("XDG_CONFIG_HOME"))
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+
+    Some(config.join("baguette tilemap editor"))
+}
+
+fn preferences_path() -> Option<PathBuf>
+{
+    config_folder().map(|folder| folder.join("preferences.json"))
+}
@@ -0,0 +1,39 @@
+/// a small seeded xorshift generator,
+/// good enough to scatter tiles around and always reproducible from its seed
+pub struct Rng(u64);
+
+impl Rng
+{
+    pub fn new(seed: u64) -> Self
+    {
+        // xorshift gets stuck on a zero state
+        Self(seed.max(1))
+    }
+
+    pub fn next_u64(&mut self) -> u64
+    {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+
+        self.0
+    }
+
+    /// returns a value between 0 included and 1 excluded
+    pub fn next_f32(&mut self) -> f32
+    {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// returns a value between 0 included and `count` excluded
+    pub fn below(&mut self, count: usize) -> usize
+    {
+        (self.next_u64() % count.max(1) as u64) as usize
+    }
+
+    /// returns true with the given probability, from 0 to 1
+    pub fn chance(&mut self, probability: f32) -> bool
+    {
+        self.next_f32() < probability
+    }
+}
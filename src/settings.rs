@@ -0,0 +1,253 @@
+use std::{collections::BTreeSet, path::{Path, PathBuf}};
+
+use baguette::app::ui;
+use serde::{Deserialize, Serialize};
+
+use crate::TilePos;
+
+/// editor preferences that belong to a single workspace,
+/// saved next to it as `<name>.bag.meta` so reopening it restores them
+#[derive(Clone)]
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProjectSettings
+{
+    /// the color behind the tiles
+    pub background: ui::Color32,
+    /// the color of the lines crossing the origin
+    pub axis_color: ui::Color32,
+    /// draws the grid along the cells at every zoom instead of by powers of ten
+    pub grid_per_tile: bool,
+
+    /// how many edits can be undone
+    pub history_depth: u16,
+    /// how many earlier saves are kept next to the workspace as `<name>.bag.1`, `<name>.bag.2` and on,
+    /// the newest first, zero overwrites the file without keeping any
+    pub backups: usize,
+    /// how many cells away from the origin a tile can be placed on either axis,
+    /// so a misclick while zoomed out can't drop one somewhere it will never be found
+    pub max_coordinate: i32,
+    /// what happens to a brush, stamp, paste or fill reaching past the coordinate limit
+    pub overflow: OverflowPolicy,
+    /// added to every cell coordinate shown or exported,
+    /// so the cells can be numbered the way the engine using the map does
+    pub origin_offset: (i32, i32),
+
+    /// the size of the tiles in the palette
+    pub asset_preview_scale: f32,
+    /// blends the pixels of scaled tiles, for tilesets that aren't pixel art
+    pub smooth_filtering: bool,
+    /// the color old tilesets paint their transparent pixels with instead of using alpha,
+    /// every pixel of exactly that color is made transparent when the sheet is decoded
+    pub color_key: Option<[u8; 3]>,
+
+    /// how many tiles the palette shows at once, zero shows them all
+    pub tiles_per_page: usize,
+    /// the palette page that was open last
+    pub palette_page: usize,
+    /// shows a second pane next to the palette, with its own page and scrolling
+    pub split_palette: bool,
+    /// the page open in the second pane
+    pub second_palette_page: usize,
+    /// the tile indices in the order the palette shows them, empty keeps the sheet order
+    pub palette_order: Vec<usize>,
+
+    /// the tile indices agents can't walk over, every other tile is walkable
+    pub solid_tiles: BTreeSet<usize>
+}
+
+/// what an edit reaching past the coordinate limit does
+#[derive(Clone, Copy, PartialEq)]
+#[derive(Serialize, Deserialize)]
+pub enum OverflowPolicy
+{
+    /// places what fits and leaves out the cells past the limit
+    Clip,
+    /// places nothing of it
+    Reject,
+    /// raises the limit until all of it fits
+    Expand
+}
+
+impl Default for ProjectSettings
+{
+    fn default() -> Self
+    {
+        Self
+        {
+            background: ui::Color32::from_gray(45),
+            axis_color: ui::Color32::GRAY,
+            grid_per_tile: false,
+
+            history_depth: 5,
+            backups: 0,
+            max_coordinate: 100_000,
+            overflow: OverflowPolicy::Clip,
+            origin_offset: (0, 0),
+
+            asset_preview_scale: 1.,
+            smooth_filtering: false,
+            color_key: None,
+
+            tiles_per_page: 256,
+            palette_page: 0,
+            split_palette: false,
+            second_palette_page: 0,
+            palette_order: Vec::new(),
+
+            solid_tiles: BTreeSet::new()
+        }
+    }
+}
+
+impl ProjectSettings
+{
+    /// reads the settings saved next to `workspace_path`,
+    /// falls back to the defaults if there are none
+    pub fn load(workspace_path: &Path) -> Self
+    {
+        std::fs::read_to_string(meta_path(workspace_path))
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    /// how the spritesheet texture is sampled wherever it's drawn
+    pub fn texture_options(&self) -> ui::TextureOptions
+    {
+        if self.smooth_filtering
+        {
+            ui::TextureOptions::LINEAR
+        }
+        else
+        {
+            ui::TextureOptions::NEAREST
+        }
+    }
+
+    /// tells if a tile can be placed at `pos`
+    pub fn in_bounds(&self, pos: TilePos) -> bool
+    {
+        // the absolute value of `i32::MIN` doesn't fit an `i32`
+        let limit = self.max_coordinate.max(0) as u32;
+        pos.x.unsigned_abs() <= limit && pos.y.unsigned_abs() <= limit
+    }
+
+    /// applies the overflow policy to the cells a single edit places tiles on,
+    /// false if none of them may be placed, otherwise the ones still past the limit are left out
+    pub fn admit(&mut self, cells: &[TilePos]) -> bool
+    {
+        match self.overflow
+        {
+            OverflowPolicy::Clip => true,
+            OverflowPolicy::Reject => cells.iter().all(|pos| self.in_bounds(*pos)),
+            OverflowPolicy::Expand =>
+            {
+                let furthest = cells.iter().map(|pos| pos.x.unsigned_abs().max(pos.y.unsigned_abs())).max();
+
+                // the limit can't go past `i32::MAX`, a cell at `i32::MIN` stays out of bounds
+                let furthest = i32::try_from(furthest.unwrap_or(0)).unwrap_or(i32::MAX);
+                self.max_coordinate = self.max_coordinate.max(furthest);
+                true
+            }
+        }
+    }
+
+    /// the coordinates `pos` is shown and exported with
+    pub fn shown(&self, pos: TilePos) -> TilePos
+    {
+        TilePos { x: pos.x + self.origin_offset.0, y: pos.y + self.origin_offset.1 }
+    }
+
+    pub fn save(&self, workspace_path: &Path) -> std::io::Result<()>
+    {
+        let json = serde_json::to_string_pretty(self)?;
+
+        std::fs::write(meta_path(workspace_path), json)
+    }
+}
+
+/// the sidecar file of a workspace, `map.bag` becomes `map.bag.meta`
+fn meta_path(workspace_path: &Path) -> PathBuf
+{
+    let mut path = workspace_path.as_os_str().to_owned();
+    path.push(".meta");
+
+    path.into()
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    /// settings limiting the map to ten cells from the origin, overflowing with `overflow`
+    fn limited(overflow: OverflowPolicy) -> ProjectSettings
+    {
+        ProjectSettings { max_coordinate: 10, overflow, ..Default::default() }
+    }
+
+    const CORNER: TilePos = TilePos { x: 10, y: -10 };
+    const PAST_CORNER: TilePos = TilePos { x: 11, y: -10 };
+
+    #[test]
+    fn the_corner_is_in_bounds_and_the_next_cell_isnt()
+    {
+        let settings = limited(OverflowPolicy::Clip);
+
+        assert!(settings.in_bounds(CORNER));
+        assert!(settings.in_bounds(TilePos { x: -10, y: 10 }));
+        assert!(!settings.in_bounds(PAST_CORNER));
+        assert!(!settings.in_bounds(TilePos { x: 10, y: -11 }));
+        assert!(!settings.in_bounds(TilePos { x: i32::MIN, y: 0 }));
+    }
+
+    #[test]
+    fn clip_admits_the_edit_and_keeps_the_limit()
+    {
+        let mut settings = limited(OverflowPolicy::Clip);
+
+        assert!(settings.admit(&[CORNER, PAST_CORNER]));
+        assert_eq!(settings.max_coordinate, 10);
+        assert!(!settings.in_bounds(PAST_CORNER));
+    }
+
+    #[test]
+    fn reject_refuses_an_edit_reaching_past_the_corner()
+    {
+        let mut settings = limited(OverflowPolicy::Reject);
+
+        assert!(settings.admit(&[CORNER]));
+        assert!(!settings.admit(&[CORNER, PAST_CORNER]));
+        assert!(settings.admit(&[]));
+        assert_eq!(settings.max_coordinate, 10);
+    }
+
+    #[test]
+    fn expand_raises_the_limit_to_the_furthest_cell()
+    {
+        let mut settings = limited(OverflowPolicy::Expand);
+
+        assert!(settings.admit(&[CORNER]));
+        assert_eq!(settings.max_coordinate, 10);
+
+        assert!(settings.admit(&[CORNER, PAST_CORNER, TilePos { x: 0, y: -12 }]));
+        assert_eq!(settings.max_coordinate, 12);
+        assert!(settings.in_bounds(PAST_CORNER));
+
+        // the limit never goes back down
+        assert!(settings.admit(&[TilePos { x: 1, y: 1 }]));
+        assert_eq!(settings.max_coordinate, 12);
+    }
+
+    #[test]
+    fn expand_stops_at_the_largest_coordinate()
+    {
+        let mut settings = limited(OverflowPolicy::Expand);
+
+        assert!(settings.admit(&[TilePos { x: i32::MIN, y: i32::MIN }]));
+        assert_eq!(settings.max_coordinate, i32::MAX);
+        assert!(settings.in_bounds(TilePos { x: i32::MAX, y: -i32::MAX }));
+        assert!(!settings.in_bounds(TilePos { x: i32::MIN, y: 0 }));
+    }
+}
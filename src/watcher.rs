@@ -0,0 +1,100 @@
+use std::{path::{Path, PathBuf}, sync::mpsc, time::{Duration, Instant}};
+
+use baguette::app::ui;
+use notify::Watcher;
+
+/// how long the spritesheet has to stay untouched after a change before it's read,
+/// art programs often write a file in a few goes
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// how many times a changed sheet that can't be read is read again before that change is given up on
+const MAX_RETRIES: u32 = 5;
+
+/// watches a spritesheet file for changes made by other programs
+pub struct SheetWatcher
+{
+    pub path: PathBuf,
+    /// the watch stops once it's dropped
+    _watcher: notify::RecommendedWatcher,
+    receiver: mpsc::Receiver<notify::Event>,
+    /// when the file last changed, until it settles and gets read
+    changed_at: Option<Instant>,
+    /// how many times the settled file failed to read since it last changed
+    retries: u32
+}
+
+impl SheetWatcher
+{
+    /// starts watching the spritesheet at `path`, waking `ctx` up whenever something happens next to it
+    pub fn new(path: PathBuf, ctx: ui::Context) -> notify::Result<Self>
+    {
+        let (sender, receiver) = mpsc::channel();
+
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>|
+        {
+            if let Ok(event) = event
+            {
+                // the receiver is gone once the watcher is dropped, there's no one to tell
+                let _ = sender.send(event);
+                ctx.request_repaint()
+            }
+        })?;
+
+        // saving often replaces the file, which would end a watch on the file itself,
+        // so the folder holding it is watched instead
+        let folder = path
+            .parent()
+            .filter(|folder| !folder.as_os_str().is_empty())
+            .unwrap_or(Path::new("."));
+
+        watcher.watch(folder, notify::RecursiveMode::NonRecursive)?;
+
+        Ok(Self { path, _watcher: watcher, receiver, changed_at: None, retries: 0 })
+    }
+
+    /// true once the file changed and then stayed untouched long enough to read,
+    /// a file whose header doesn't read is tried again a few times in case it was still being written
+    pub fn poll(&mut self, ctx: &ui::Context) -> bool
+    {
+        for event in self.receiver.try_iter()
+        {
+            let touched = !event.kind.is_access()
+                && event.paths.iter().any(|path| path.file_name() == self.path.file_name());
+
+            if touched
+            {
+                self.changed_at = Some(Instant::now());
+                self.retries = 0
+            }
+        }
+
+        let Some(changed_at) = self.changed_at else
+        {
+            return false
+        };
+
+        if let Some(remaining) = DEBOUNCE.checked_sub(changed_at.elapsed())
+        {
+            ctx.request_repaint_after(remaining);
+            return false
+        }
+
+        // the header is enough to tell the file is there, the reload decodes the pixels once
+        if image::image_dimensions(&self.path).is_ok()
+        {
+            self.changed_at = None;
+            return true
+        }
+
+        // most likely a partial write, the rest of it should be there after another wait
+        self.retries += 1;
+        self.changed_at = (self.retries < MAX_RETRIES).then(Instant::now);
+
+        if self.changed_at.is_some()
+        {
+            ctx.request_repaint_after(DEBOUNCE)
+        }
+
+        false
+    }
+}